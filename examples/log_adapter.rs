@@ -2,6 +2,8 @@
 
 use chrysalis_rs::{Adapter, AdapterOptions, LogEntry, LogLevel, Error};
 use log::{Level, Record};
+use serde_json::Value;
+use std::collections::HashMap;
 
 /// Adapter for the standard log crate
 pub struct LogAdapter {
@@ -41,26 +43,32 @@ impl Default for LogAdapter {
 
 impl Adapter<Record<'_>> for LogAdapter {
     fn convert(&self, record: &Record) -> Result<LogEntry, Error> {
-        // Create the basic log entry
-        let mut entry = LogEntry::new(
+        // Collect module path and target as context
+        let mut context = HashMap::new();
+        if let Some(module_path) = record.module_path() {
+            context.insert("module_path".to_string(), Value::from(module_path));
+        }
+        context.insert("target".to_string(), Value::from(record.target()));
+        
+        // Add source information if enabled
+        let source = if self.options.include_source {
+            record.file().map(|file| (file, record.line().unwrap_or(0)))
+        } else {
+            None
+        };
+        
+        let mut entry = LogEntry::from_parts(
             record.args().to_string(),
             self.convert_level(record.level()),
+            source,
+            context,
         );
         
-        // Add source information if enabled
-        if self.options.include_source {
-            if let Some(file) = record.file() {
-                entry = entry.with_source(file, record.line().unwrap_or(0));
-            }
-        }
+        self.options.apply_module_source(&mut entry, record.module_path());
+        self.options.default_context.apply(&mut entry);
         
-        // Add module path as context
-        if let Some(module_path) = record.module_path() {
-            entry.add_context("module_path", module_path)?;
-        }
-        
-        // Add target as context
-        entry.add_context("target", record.target())?;
+        // Guard against records stamped by a bad clock
+        self.options.clamp_timestamp(&mut entry)?;
         
         Ok(entry)
     }
@@ -92,6 +100,12 @@ impl ChrysalisLogger {
     }
 }
 
+impl Default for ChrysalisLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl log::Log for ChrysalisLogger {
     fn enabled(&self, _metadata: &log::Metadata) -> bool {
         true // Log everything
@@ -1,6 +1,15 @@
+use std::collections::HashMap;
+use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
 use crate::core::{LogEntry, LogLevel};
-use crate::error::Result;
+use crate::error::{Result, Error};
+use crate::util::format_timestamp;
 
 /// Trait for adapting external logging systems to ChrysalisRS
 pub trait Adapter<T> {
@@ -9,6 +18,14 @@ pub trait Adapter<T> {
     
     /// Configure the adapter with options
     fn configure(&mut self, options: AdapterOptions);
+    
+    /// Convert a ChrysalisRS LogEntry back into the external log type
+    ///
+    /// Adapters that can't reconstruct the external type keep the default,
+    /// which returns an error.
+    fn convert_back(&self, _entry: &LogEntry) -> Result<T> {
+        Err(Error::LoggingError("Reverse conversion is not supported by this adapter".to_string()))
+    }
 }
 
 /// Options for adapters
@@ -22,6 +39,17 @@ pub struct AdapterOptions {
     pub include_stack_traces: bool,
     /// Optional context extraction function (as string representation)
     pub context_extractor: Option<String>,
+    /// Level assigned to logs that carry no level of their own
+    pub default_level: LogLevel,
+    /// Maximum allowed distance between an entry's timestamp and now
+    ///
+    /// Timestamps outside the window are clamped to its edge and the
+    /// original is kept in the `original_timestamp` context field.
+    pub clamp_timestamp_skew: Option<Duration>,
+    /// Context fields added to converted entries, optionally per level
+    pub default_context: DefaultContext,
+    /// Use the module path as `metadata.source` when no file is known
+    pub source_from_module: bool,
 }
 
 impl Default for AdapterOptions {
@@ -31,10 +59,101 @@ impl Default for AdapterOptions {
             include_thread: true,
             include_stack_traces: true,
             context_extractor: None,
+            default_level: LogLevel::Info,
+            clamp_timestamp_skew: None,
+            default_context: DefaultContext::default(),
+            source_from_module: false,
+        }
+    }
+}
+
+/// Default context fields merged into entries based on their level
+///
+/// Fields already present on an entry are never overwritten.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultContext {
+    all: HashMap<String, Value>,
+    per_level: HashMap<LogLevel, HashMap<String, Value>>,
+}
+
+impl DefaultContext {
+    /// Create an empty set of default fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add a field attached to entries of every level
+    pub fn with_field<T: Serialize>(mut self, key: impl Into<String>, value: T) -> Result<Self> {
+        let value = serde_json::to_value(value).map_err(Error::SerializationError)?;
+        self.all.insert(key.into(), value);
+        Ok(self)
+    }
+    
+    /// Add a field attached only to entries of the given level
+    pub fn with_level_field<T: Serialize>(
+        mut self,
+        level: LogLevel,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<Self> {
+        let value = serde_json::to_value(value).map_err(Error::SerializationError)?;
+        self.per_level.entry(level).or_default().insert(key.into(), value);
+        Ok(self)
+    }
+    
+    /// Check if no default fields are configured
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty() && self.per_level.values().all(HashMap::is_empty)
+    }
+    
+    /// Merge the default fields matching the entry's level into its context
+    pub fn apply(&self, entry: &mut LogEntry) {
+        let level_fields = self.per_level.get(&entry.level);
+        let fields = level_fields.into_iter().flatten().chain(&self.all);
+        
+        for (key, value) in fields {
+            if !entry.context.contains_key(key) {
+                entry.context.insert(key.clone(), value.clone());
+            }
         }
     }
 }
 
+impl AdapterOptions {
+    /// Clamp the entry's timestamp to the configured skew window around now
+    ///
+    /// Does nothing when `clamp_timestamp_skew` is unset.
+    pub fn clamp_timestamp(&self, entry: &mut LogEntry) -> Result<()> {
+        let Some(skew) = self.clamp_timestamp_skew else {
+            return Ok(());
+        };
+        
+        let now = Utc::now();
+        let window = chrono::Duration::from_std(skew).unwrap_or(chrono::Duration::MAX);
+        let latest = now.checked_add_signed(window).unwrap_or(DateTime::<Utc>::MAX_UTC);
+        let earliest = now.checked_sub_signed(window).unwrap_or(DateTime::<Utc>::MIN_UTC);
+        
+        let original = entry.metadata.timestamp;
+        let clamped = original.clamp(earliest, latest);
+        if clamped != original {
+            entry.add_context("original_timestamp", format_timestamp(&original))?;
+            entry.metadata.timestamp = clamped;
+        }
+        Ok(())
+    }
+    
+    /// Fill `metadata.source` from the module path if the entry has no file
+    ///
+    /// Does nothing unless `source_from_module` is set, so the UI gets a
+    /// consistent "where" field even for records without file information.
+    pub fn apply_module_source(&self, entry: &mut LogEntry, module_path: Option<&str>) {
+        if !self.source_from_module || entry.metadata.source.is_some() {
+            return;
+        }
+        entry.metadata.source = module_path.map(str::to_string);
+    }
+}
+
 /// Standard adapter for simple string logs
 pub struct StandardAdapter<T> {
     options: AdapterOptions,
@@ -65,14 +184,455 @@ impl<T> Default for StandardAdapter<T> {
     }
 }
 
-impl<T: AsRef<str>> Adapter<T> for StandardAdapter<T> {
+/// String-like log types handled by `StandardAdapter`
+pub trait StandardLog: AsRef<str> {
+    /// Rebuild the log from an entry's message
+    ///
+    /// Owned string types return the message; borrowed ones such as `&str`
+    /// can't and keep the default, which returns `None`.
+    fn from_message(_message: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+impl StandardLog for String {
+    fn from_message(message: &str) -> Option<Self> {
+        Some(message.to_string())
+    }
+}
+
+impl StandardLog for Box<str> {
+    fn from_message(message: &str) -> Option<Self> {
+        Some(message.into())
+    }
+}
+
+impl StandardLog for Rc<str> {
+    fn from_message(message: &str) -> Option<Self> {
+        Some(message.into())
+    }
+}
+
+impl StandardLog for Arc<str> {
+    fn from_message(message: &str) -> Option<Self> {
+        Some(message.into())
+    }
+}
+
+impl StandardLog for Cow<'_, str> {
+    fn from_message(message: &str) -> Option<Self> {
+        Some(Cow::Owned(message.to_string()))
+    }
+}
+
+impl StandardLog for &str {}
+
+impl<T: StandardLog> Adapter<T> for StandardAdapter<T> {
     fn convert(&self, external_log: &T) -> Result<LogEntry> {
         let message = external_log.as_ref().to_string();
-        let entry = LogEntry::new(message, LogLevel::Info);
+        let mut entry = LogEntry::new(message, self.options.default_level);
+        self.options.default_context.apply(&mut entry);
+        self.options.clamp_timestamp(&mut entry)?;
         Ok(entry)
     }
     
     fn configure(&mut self, options: AdapterOptions) {
         self.options = options;
     }
-}
\ No newline at end of file
+    
+    fn convert_back(&self, entry: &LogEntry) -> Result<T> {
+        T::from_message(&entry.message).ok_or_else(|| Error::LoggingError(
+            "Reverse conversion needs an owned log type".to_string()
+        ))
+    }
+}
+
+/// Output stream a line was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+impl Stream {
+    /// Name used in the `stream` context field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stream::Stdout => "stdout",
+            Stream::Stderr => "stderr",
+        }
+    }
+}
+
+/// Adapter for lines read from a child process's stdout or stderr
+///
+/// Stdout lines default to `Info` and stderr lines to `Warn`. Each entry
+/// gets a `stream` context field. With level inference enabled, a leading
+/// level tag such as `ERROR:` or `[debug]` overrides the stream default.
+pub struct LineAdapter {
+    options: AdapterOptions,
+    stdout_level: LogLevel,
+    stderr_level: LogLevel,
+    infer_level: bool,
+}
+
+impl LineAdapter {
+    /// Create a new line adapter
+    pub fn new() -> Self {
+        Self {
+            options: AdapterOptions::default(),
+            stdout_level: LogLevel::Info,
+            stderr_level: LogLevel::Warn,
+            infer_level: false,
+        }
+    }
+    
+    /// Set the default levels for stdout and stderr lines
+    pub fn with_stream_levels(mut self, stdout: LogLevel, stderr: LogLevel) -> Self {
+        self.stdout_level = stdout;
+        self.stderr_level = stderr;
+        self
+    }
+    
+    /// Enable or disable inferring the level from a line prefix
+    pub fn with_level_inference(mut self, infer: bool) -> Self {
+        self.infer_level = infer;
+        self
+    }
+    
+    /// Convert a single line into a log entry
+    pub fn convert_line(&self, line: &str, stream: Stream) -> Result<LogEntry> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let level = self.infer_level
+            .then(|| infer_level_prefix(line))
+            .flatten()
+            .unwrap_or(match stream {
+                Stream::Stdout => self.stdout_level,
+                Stream::Stderr => self.stderr_level,
+            });
+        
+        let mut entry = LogEntry::new(line, level);
+        entry.add_context("stream", stream.as_str())?;
+        self.options.default_context.apply(&mut entry);
+        Ok(entry)
+    }
+    
+    /// Configure the adapter with options
+    pub fn configure(&mut self, options: AdapterOptions) {
+        self.options = options;
+    }
+}
+
+impl Default for LineAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Predicate deciding whether a line continues the previous entry
+type ContinuationFn = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Assembles multi-line log records, such as stack traces, into single entries
+///
+/// Each line that isn't a continuation starts a new entry with the line as
+/// its message. Continuation lines (by default, lines starting with
+/// whitespace) are appended to the pending entry's `body`, one per line.
+/// Completed entries are returned once the next entry starts; call `finish`
+/// at the end of input to get the last one.
+pub struct MultiLineAssembler {
+    is_continuation: ContinuationFn,
+    level: LogLevel,
+    pending: Option<LogEntry>,
+}
+
+impl MultiLineAssembler {
+    /// Create an assembler treating indented lines as continuations
+    pub fn new() -> Self {
+        Self {
+            is_continuation: Box::new(|line| line.starts_with(char::is_whitespace)),
+            level: LogLevel::Info,
+            pending: None,
+        }
+    }
+    
+    /// Use a custom predicate for continuation lines
+    pub fn with_continuation<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.is_continuation = Box::new(predicate);
+        self
+    }
+    
+    /// Set the level of assembled entries
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+    
+    /// Feed one line, returning the previous entry if this line completes it
+    ///
+    /// A continuation line with no pending entry starts a new entry.
+    pub fn push_line(&mut self, line: &str) -> Option<LogEntry> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        
+        if (self.is_continuation)(line) {
+            if let Some(entry) = self.pending.as_mut() {
+                match entry.body.as_mut() {
+                    Some(body) => {
+                        body.push('\n');
+                        body.push_str(line);
+                    },
+                    None => entry.body = Some(line.to_string()),
+                }
+                return None;
+            }
+        }
+        
+        self.pending.replace(LogEntry::new(line, self.level))
+    }
+    
+    /// Take the pending entry at the end of input
+    pub fn finish(&mut self) -> Option<LogEntry> {
+        self.pending.take()
+    }
+}
+
+impl Default for MultiLineAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a leading level tag like `ERROR:` or `[warn]`
+fn infer_level_prefix(line: &str) -> Option<LogLevel> {
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix('[').unwrap_or(rest);
+    
+    let end = rest.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (word, tail) = rest.split_at(end);
+    if !(tail.starts_with(':') || tail.starts_with(']')) {
+        return None;
+    }
+    word.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_standard_adapter_default_level() {
+        let adapter = StandardAdapter::<String>::new();
+        let entry = adapter.convert(&"plain message".to_string()).unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        
+        let adapter = StandardAdapter::<String>::with_options(AdapterOptions {
+            default_level: LogLevel::Debug,
+            ..Default::default()
+        });
+        let entry = adapter.convert(&"plain message".to_string()).unwrap();
+        assert_eq!(entry.level, LogLevel::Debug);
+        assert_eq!(entry.message, "plain message");
+    }
+    
+    #[test]
+    fn test_clamp_timestamp_skew() {
+        let options = AdapterOptions {
+            clamp_timestamp_skew: Some(Duration::from_secs(300)),
+            ..Default::default()
+        };
+        
+        let mut entry = LogEntry::new("from a bad clock", LogLevel::Info);
+        let future = Utc::now() + chrono::Duration::days(365);
+        entry.metadata.timestamp = future;
+        options.clamp_timestamp(&mut entry).unwrap();
+        
+        assert!(entry.metadata.timestamp <= Utc::now() + chrono::Duration::seconds(300));
+        assert_eq!(
+            entry.context["original_timestamp"],
+            serde_json::json!(format_timestamp(&future))
+        );
+        
+        let mut entry = LogEntry::new("from a good clock", LogLevel::Info);
+        let timestamp = entry.metadata.timestamp;
+        options.clamp_timestamp(&mut entry).unwrap();
+        assert_eq!(entry.metadata.timestamp, timestamp);
+        assert!(!entry.context.contains_key("original_timestamp"));
+    }
+    
+    #[test]
+    fn test_clamp_timestamp_huge_skew() {
+        let options = AdapterOptions {
+            clamp_timestamp_skew: Some(Duration::MAX),
+            ..Default::default()
+        };
+        
+        let mut entry = LogEntry::new("far future", LogLevel::Info);
+        let future = Utc::now() + chrono::Duration::days(365 * 100);
+        entry.metadata.timestamp = future;
+        options.clamp_timestamp(&mut entry).unwrap();
+        assert_eq!(entry.metadata.timestamp, future);
+        
+        let mut entry = LogEntry::new("far past", LogLevel::Info);
+        let past = Utc::now() - chrono::Duration::days(365 * 100);
+        entry.metadata.timestamp = past;
+        options.clamp_timestamp(&mut entry).unwrap();
+        assert_eq!(entry.metadata.timestamp, past);
+        assert!(!entry.context.contains_key("original_timestamp"));
+    }
+    
+    #[test]
+    fn test_source_from_module() {
+        let record = log::Record::builder()
+            .args(format_args!("pool exhausted"))
+            .module_path(Some("app::db::pool"))
+            .build();
+        assert!(record.file().is_none());
+        
+        let mut entry = LogEntry::new(record.args().to_string(), LogLevel::Warn);
+        AdapterOptions::default().apply_module_source(&mut entry, record.module_path());
+        assert!(entry.metadata.source.is_none());
+        
+        let options = AdapterOptions {
+            source_from_module: true,
+            ..Default::default()
+        };
+        options.apply_module_source(&mut entry, record.module_path());
+        assert_eq!(entry.metadata.source.as_deref(), Some("app::db::pool"));
+        
+        let mut located = LogEntry::new("pool exhausted", LogLevel::Warn).with_source("src/db.rs", 12);
+        options.apply_module_source(&mut located, record.module_path());
+        assert_eq!(located.metadata.source.as_deref(), Some("src/db.rs"));
+    }
+    
+    #[test]
+    fn test_level_default_context() {
+        let defaults = DefaultContext::new()
+            .with_field("service", "billing").unwrap()
+            .with_level_field(LogLevel::Error, "alert_team", true).unwrap();
+        
+        let mut info = LogEntry::new("Invoice created", LogLevel::Info);
+        defaults.apply(&mut info);
+        assert_eq!(info.context["service"], "billing");
+        assert!(!info.context.contains_key("alert_team"));
+        
+        let mut error = LogEntry::new("Invoice failed", LogLevel::Error);
+        error.add_context("service", "payments").unwrap();
+        defaults.apply(&mut error);
+        assert_eq!(error.context["alert_team"], true);
+        assert_eq!(error.context["service"], "payments");
+        
+        let adapter = StandardAdapter::<String>::with_options(AdapterOptions {
+            default_level: LogLevel::Error,
+            default_context: defaults,
+            ..Default::default()
+        });
+        let entry = adapter.convert(&"converted".to_string()).unwrap();
+        assert_eq!(entry.context["alert_team"], true);
+    }
+    
+    #[test]
+    fn test_standard_adapter_round_trip() {
+        let adapter = StandardAdapter::<String>::new();
+        let original = "Service started".to_string();
+        
+        let entry = adapter.convert(&original).unwrap();
+        assert_eq!(adapter.convert_back(&entry).unwrap(), original);
+        
+        let adapter: &dyn Adapter<String> = &adapter;
+        assert_eq!(adapter.convert_back(&entry).unwrap(), original);
+        
+        fn round_trip<A: Adapter<Arc<str>>>(adapter: &A, log: Arc<str>) -> Arc<str> {
+            let entry = adapter.convert(&log).unwrap();
+            adapter.convert_back(&entry).unwrap()
+        }
+        assert_eq!(&*round_trip(&StandardAdapter::new(), "Shared".into()), "Shared");
+    }
+    
+    #[test]
+    fn test_standard_adapter_borrowed_str() {
+        let adapter = StandardAdapter::<&str>::new();
+        let entry = adapter.convert(&"borrowed line").unwrap();
+        assert_eq!(entry.message, "borrowed line");
+        assert!(adapter.convert_back(&entry).is_err());
+    }
+    
+    #[test]
+    fn test_convert_back_unsupported_by_default() {
+        struct OneWay;
+        
+        impl Adapter<u32> for OneWay {
+            fn convert(&self, code: &u32) -> Result<LogEntry> {
+                Ok(LogEntry::new(format!("code {}", code), LogLevel::Info))
+            }
+            
+            fn configure(&mut self, _options: AdapterOptions) {}
+        }
+        
+        let entry = OneWay.convert(&7).unwrap();
+        assert!(matches!(OneWay.convert_back(&entry), Err(Error::LoggingError(_))));
+    }
+    
+    #[test]
+    fn test_line_adapter_streams() {
+        let adapter = LineAdapter::new();
+        
+        let out = adapter.convert_line("server listening\n", Stream::Stdout).unwrap();
+        assert_eq!(out.level, LogLevel::Info);
+        assert_eq!(out.message, "server listening");
+        assert_eq!(out.context["stream"], "stdout");
+        
+        let err = adapter.convert_line("deprecated flag used", Stream::Stderr).unwrap();
+        assert_eq!(err.level, LogLevel::Warn);
+        assert_eq!(err.context["stream"], "stderr");
+    }
+    
+    #[test]
+    fn test_line_adapter_level_inference() {
+        let adapter = LineAdapter::new().with_level_inference(true);
+        
+        let entry = adapter.convert_line("ERROR: disk full", Stream::Stdout).unwrap();
+        assert_eq!(entry.level, LogLevel::Error);
+        let entry = adapter.convert_line("[debug] cache warm", Stream::Stderr).unwrap();
+        assert_eq!(entry.level, LogLevel::Debug);
+        let entry = adapter.convert_line("error handling enabled", Stream::Stdout).unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+    }
+    
+    #[test]
+    fn test_multi_line_assembler_stack_trace() {
+        let input = "\
+Exception in thread \"main\" java.lang.IllegalStateException: pool closed
+\tat com.example.Pool.acquire(Pool.java:42)
+\tat com.example.Main.main(Main.java:7)
+Caused by: java.io.IOException: broken pipe
+\tat com.example.Conn.write(Conn.java:99)
+Server shutting down
+";
+        let mut assembler = MultiLineAssembler::new()
+            .with_level(LogLevel::Error)
+            .with_continuation(|line| {
+                line.starts_with(char::is_whitespace) || line.starts_with("Caused by:")
+            });
+        
+        let mut entries: Vec<LogEntry> = input.lines().filter_map(|line| assembler.push_line(line)).collect();
+        entries.extend(assembler.finish());
+        
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "Exception in thread \"main\" java.lang.IllegalStateException: pool closed");
+        assert_eq!(entries[0].level, LogLevel::Error);
+        let body = entries[0].body.as_deref().unwrap();
+        assert_eq!(body.lines().count(), 4);
+        assert!(body.starts_with("\tat com.example.Pool.acquire"));
+        assert!(body.contains("Caused by: java.io.IOException: broken pipe"));
+        
+        assert_eq!(entries[1].message, "Server shutting down");
+        assert!(entries[1].body.is_none());
+    }
+}
@@ -0,0 +1,126 @@
+//! Borrowed, low-allocation view of a serialized log entry
+//!
+//! `LogEntryRef` borrows strings directly from the input buffer where the
+//! JSON allows it, which avoids most allocations when reprocessing large
+//! NDJSON files.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::{LogEntry, LogLevel, MetaData};
+use crate::error::{Result, Error};
+use crate::finite::NonFiniteMode;
+
+/// Borrowed view of a log entry deserialized from a JSON string
+///
+/// The view cannot outlive the input it was parsed from: `'a` is the
+/// lifetime of that buffer, so an NDJSON reader must keep each line alive
+/// for as long as its `LogEntryRef` is in use. Strings containing JSON
+/// escape sequences can't be borrowed and fall back to `Cow::Owned`.
+/// Metadata is always parsed into an owned `MetaData`. Call `into_owned`
+/// to detach the entry from the input.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogEntryRef<'a> {
+    /// The primary log message
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+    /// Optional long-form detail accompanying the message
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Log severity level
+    pub level: LogLevel,
+    /// Metadata about the log
+    #[serde(default = "MetaData::omitted")]
+    pub metadata: MetaData,
+    /// Context fields, with string values borrowed where possible
+    ///
+    /// Keys are owned: serde only borrows `Cow` strings that are fields or
+    /// variants, not map keys.
+    #[serde(borrow, default)]
+    pub context: HashMap<String, ContextValueRef<'a>>,
+    /// Which source set each context field, when recorded
+    #[serde(rename = "_provenance", default)]
+    pub provenance: HashMap<String, String>,
+}
+
+/// A context value that borrows its contents if it is a plain string
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ContextValueRef<'a> {
+    /// A string value
+    Str(#[serde(borrow)] Cow<'a, str>),
+    /// Any other JSON value
+    Other(Value),
+}
+
+impl ContextValueRef<'_> {
+    /// Whether the value refers into the input buffer without allocating
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ContextValueRef::Str(Cow::Borrowed(_)))
+    }
+    
+    /// Convert into an owned JSON value
+    pub fn into_value(self) -> Value {
+        match self {
+            ContextValueRef::Str(s) => Value::String(s.into_owned()),
+            ContextValueRef::Other(v) => v,
+        }
+    }
+}
+
+impl<'a> LogEntryRef<'a> {
+    /// Parse a borrowed entry from a JSON string
+    pub fn from_json(input: &'a str) -> Result<Self> {
+        serde_json::from_str(input).map_err(Error::SerializationError)
+    }
+    
+    /// Convert into an owned `LogEntry`, copying any borrowed strings
+    pub fn into_owned(self) -> LogEntry {
+        LogEntry {
+            message: self.message.into_owned(),
+            body: self.body,
+            level: self.level,
+            metadata: self.metadata,
+            context: self.context
+                .into_iter()
+                .map(|(k, v)| (k, v.into_value()))
+                .collect(),
+            provenance: self.provenance,
+            max_context_fields: None,
+            non_finite: NonFiniteMode::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_borrowed_parse_avoids_string_allocations() {
+        let mut entry = LogEntry::new("Cache warmed", LogLevel::Info);
+        entry.add_context("region", "eu-west-1").unwrap();
+        entry.add_context("node", "cache-7").unwrap();
+        entry.add_context("keys", 1024).unwrap();
+        entry.add_context("quoted", "say \"hi\"").unwrap();
+        let json = entry.to_json().unwrap();
+        
+        let borrowed = LogEntryRef::from_json(&json).unwrap();
+        assert!(matches!(borrowed.message, Cow::Borrowed(_)));
+        
+        // Plain string values are zero-copy; only the escaped value and the
+        // non-string value need their own allocation
+        let borrowed_values = borrowed.context.values().filter(|v| v.is_borrowed()).count();
+        assert_eq!(borrowed_values, 2);
+        assert!(matches!(&borrowed.context["quoted"], ContextValueRef::Str(Cow::Owned(_))));
+        
+        // Owned parsing allocates every one of those strings
+        let owned: LogEntry = serde_json::from_str(&json).unwrap();
+        let converted = borrowed.into_owned();
+        assert!(converted.semantically_eq(&owned));
+        assert_eq!(converted.metadata.id, owned.metadata.id);
+    }
+}
@@ -0,0 +1,201 @@
+//! Entry checksums for pipeline integrity checks
+
+use std::any::Any;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+use crate::extensions::Extension;
+use crate::util::crc32;
+
+/// Metadata field holding the entry checksum
+pub const CHECKSUM_FIELD: &str = "checksum";
+
+/// Extension that attaches a CRC-32 checksum of each entry's content
+///
+/// The checksum covers the entry's canonical JSON (see
+/// `LogEntry::to_canonical_json`) without the checksum field itself, and is
+/// stored as 8 lowercase hex digits in `metadata.checksum`. Downstream
+/// consumers can detect corruption with `ChecksumExtension::verify`.
+///
+/// Entries with omitted metadata (see `LogEntry::lightweight`) have nowhere
+/// to carry the checksum and are rejected. Register it last: `ExtensionRegistry::process_all` runs extensions in
+/// registration order, and any change made after the checksum invalidates it.
+pub struct ChecksumExtension {
+    enabled: bool,
+}
+
+impl ChecksumExtension {
+    /// Create a new checksum extension
+    pub fn new() -> Self {
+        Self { enabled: true }
+    }
+    
+    /// Compute the checksum of an entry, ignoring any existing checksum
+    pub fn checksum(entry: &LogEntry) -> Result<String> {
+        let canonical = if entry.metadata.custom.contains_key(CHECKSUM_FIELD) {
+            let mut unsigned = entry.clone();
+            unsigned.metadata.custom.remove(CHECKSUM_FIELD);
+            unsigned.to_canonical_json()?
+        } else {
+            entry.to_canonical_json()?
+        };
+        Ok(format!("{:08x}", crc32(canonical.as_bytes())))
+    }
+    
+    /// Check that an entry carries a checksum matching its content
+    pub fn verify(entry: &LogEntry) -> bool {
+        let Some(stored) = entry.metadata.custom.get(CHECKSUM_FIELD).and_then(|v| v.as_str()) else {
+            return false;
+        };
+        Self::checksum(entry).is_ok_and(|computed| computed == stored)
+    }
+}
+
+impl Default for ChecksumExtension {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extension for ChecksumExtension {
+    fn name(&self) -> &str {
+        "checksum"
+    }
+    
+    fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+    
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+    
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    
+    fn process(&self, entry: &mut LogEntry) -> Result<()> {
+        if entry.metadata.is_omitted() {
+            return Err(Error::ExtensionError(
+                "Cannot checksum an entry without metadata".to_string()
+            ));
+        }
+        let checksum = Self::checksum(entry)?;
+        entry.metadata.add_field(CHECKSUM_FIELD, checksum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    use crate::extensions::ExtensionRegistry;
+    
+    /// Extension that stamps a context field onto every entry
+    struct HostStamp;
+    
+    impl Extension for HostStamp {
+        fn name(&self) -> &str {
+            "host_stamp"
+        }
+        
+        fn initialize(&mut self) -> Result<()> {
+            Ok(())
+        }
+        
+        fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+        
+        fn is_enabled(&self) -> bool {
+            true
+        }
+        
+        fn set_enabled(&mut self, _enabled: bool) {}
+        
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+        
+        fn process(&self, entry: &mut LogEntry) -> Result<()> {
+            entry.add_context("host", "web-01")?;
+            Ok(())
+        }
+    }
+    
+    fn checksummed_entry() -> LogEntry {
+        let mut registry = ExtensionRegistry::new();
+        registry.register(ChecksumExtension::new()).unwrap();
+        
+        let mut entry = LogEntry::new("Transfer complete", LogLevel::Info);
+        entry.add_context("bytes", 52_428_800).unwrap();
+        registry.process_all(&mut entry).unwrap();
+        entry
+    }
+    
+    #[test]
+    fn test_checksum_verifies() {
+        let entry = checksummed_entry();
+        let stored = entry.metadata.custom[CHECKSUM_FIELD].as_str().unwrap();
+        assert_eq!(stored.len(), 8);
+        assert!(ChecksumExtension::verify(&entry));
+        
+        // Survives a serialization round trip
+        let parsed: LogEntry = serde_json::from_str(&entry.to_json().unwrap()).unwrap();
+        assert!(ChecksumExtension::verify(&parsed));
+    }
+    
+    #[test]
+    fn test_corrupted_entry_fails_verification() {
+        let mut entry = checksummed_entry();
+        entry.context.insert("bytes".to_string(), 52_428_801.into());
+        assert!(!ChecksumExtension::verify(&entry));
+        
+        let unchecked = LogEntry::new("No checksum", LogLevel::Info);
+        assert!(!ChecksumExtension::verify(&unchecked));
+    }
+    
+    #[test]
+    fn test_checksum_rejects_omitted_metadata() {
+        let mut entry = LogEntry::lightweight("Transfer complete", LogLevel::Info);
+        assert!(ChecksumExtension::new().process(&mut entry).is_err());
+        assert!(entry.metadata.is_omitted());
+    }
+    
+    #[test]
+    fn test_checksum_after_mutating_extension() {
+        let mut registry = ExtensionRegistry::new();
+        registry.register(HostStamp).unwrap();
+        registry.register(ChecksumExtension::new()).unwrap();
+        
+        let mut entry = LogEntry::new("Transfer complete", LogLevel::Info);
+        registry.process_all(&mut entry).unwrap();
+        assert_eq!(entry.context["host"], "web-01");
+        assert!(ChecksumExtension::verify(&entry));
+        
+        // Registered the other way round, the stamp lands after the checksum
+        let mut registry = ExtensionRegistry::new();
+        registry.register(ChecksumExtension::new()).unwrap();
+        registry.register(HostStamp).unwrap();
+        
+        let mut entry = LogEntry::new("Transfer complete", LogLevel::Info);
+        registry.process_all(&mut entry).unwrap();
+        assert!(!ChecksumExtension::verify(&entry));
+    }
+}
@@ -1,11 +1,42 @@
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use crate::error::{Result, Error};
+use crate::util::{
+    apply_json_diff, canonical_json, capture_frames, edit_distance, format_iso_duration,
+    format_timestamp, flatten_json, json_depth_exceeds, json_diff, merge_json_values,
+    nested_field_path, sanitize_field_name, simple_hash, string_to_log_level,
+};
+use crate::finite::{to_value_finite, NonFiniteMode};
+use crate::formatter::LevelGlyphs;
+use crate::redact::{RedactedDebug, Redactor};
+use crate::sampling::TRACE_ID_FIELD;
+use crate::value_formatter::ValueFormatterRegistry;
+
+/// Context field holding the experiment variants of an entry
+const EXPERIMENTS_FIELD: &str = "experiments";
+
+/// Context field holding a metrics snapshot
+const METRICS_FIELD: &str = "metrics";
+
+/// Next value handed out by `LogEntry::with_sequence`
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Reference point for `LogEntry::with_uptime`, captured on first use
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
 
 /// Log levels supported by ChrysalisRS
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+///
+/// Deserialization is lenient: unknown level names (such as `"notice"`)
+/// map to `Info` so bridged logs are never dropped. Use `str::parse` for
+/// strict parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     /// Trace level logging (lowest level)
@@ -38,6 +69,91 @@ impl std::fmt::Display for LogLevel {
     }
 }
 
+impl LogLevel {
+    /// All levels, from lowest to highest
+    pub const ALL: [LogLevel; 7] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+        LogLevel::Critical,
+        LogLevel::Fatal,
+    ];
+    
+    /// Short symbol for the level in terminal UIs
+    ///
+    /// A rendering helper independent of color; see `LevelGlyphs` to
+    /// override the defaults.
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "\u{22ef}",
+            LogLevel::Debug => "\u{2699}",
+            LogLevel::Info => "\u{2139}",
+            LogLevel::Warn => "\u{26a0}",
+            LogLevel::Error => "\u{2716}",
+            LogLevel::Critical => "\u{203c}",
+            LogLevel::Fatal => "\u{2620}",
+        }
+    }
+    
+    /// Map an HTTP status code to a level for access logs
+    ///
+    /// Informational, success and redirect codes are `Info`, client errors
+    /// `Warn` and server errors `Error`. Codes outside 100-599 fall into the
+    /// nearest band: anything below 400 is `Info`, anything from 500 `Error`.
+    pub fn from_http_status(status: u16) -> LogLevel {
+        match status {
+            0..=399 => LogLevel::Info,
+            400..=499 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+    
+    /// Parse a level strictly, suggesting the closest name on failure
+    ///
+    /// Intended for configuration values: a typo such as `"waring"` fails
+    /// with "did you mean 'warn'?" based on edit distance.
+    pub fn parse_with_suggestion(s: &str) -> Result<LogLevel> {
+        s.parse().map_err(|_| {
+            let input = s.to_lowercase();
+            let closest = Self::ALL
+                .iter()
+                .map(|level| level.to_string())
+                .min_by_key(|name| edit_distance(&input, name))
+                .unwrap_or_default();
+            Error::LoggingError(format!("Unknown log level '{}', did you mean '{}'?", s, closest))
+        })
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" | "err" => Ok(LogLevel::Error),
+            "critical" | "crit" => Ok(LogLevel::Critical),
+            "fatal" => Ok(LogLevel::Fatal),
+            _ => Err(Error::LoggingError(format!("Unknown log level '{}'", s))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LogLevel {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let level = std::borrow::Cow::<'de, str>::deserialize(deserializer)?;
+        Ok(string_to_log_level(&level))
+    }
+}
+
 /// Metadata for a log entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetaData {
@@ -45,18 +161,45 @@ pub struct MetaData {
     pub id: Uuid,
     /// Timestamp when the log was created
     pub timestamp: DateTime<Utc>,
+    /// When the entry was received by an ingesting system, if recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<DateTime<Utc>>,
     /// Source of the log (file, module, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
     /// Line number where the log originated
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<u32>,
+    /// Column number where the log originated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// Function where the log originated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
     /// Thread or task ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thread: Option<String>,
+    /// Hierarchical logger name such as `com.app.db`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// Process-wide sequence number, for ordering entries with equal timestamps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    /// Monotonic nanoseconds since the process clock started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_ns: Option<u64>,
+    /// Intended retention in seconds, counted from `timestamp`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<u64>,
+    /// W3C trace flags from an upstream `traceparent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_flags: Option<u8>,
     /// Custom fields
     #[serde(flatten)]
     pub custom: HashMap<String, serde_json::Value>,
+    /// Whether the metadata is left out of serialized output
+    #[serde(skip)]
+    pub omitted: bool,
 }
 
 impl Default for MetaData {
@@ -64,10 +207,19 @@ impl Default for MetaData {
         Self {
             id: Uuid::new_v4(),
             timestamp: Utc::now(),
+            received_at: None,
             source: None,
             line: None,
+            column: None,
+            function: None,
             thread: None,
+            logger: None,
+            sequence: None,
+            uptime_ns: None,
+            ttl_seconds: None,
+            trace_flags: None,
             custom: HashMap::new(),
+            omitted: false,
         }
     }
 }
@@ -78,6 +230,129 @@ impl MetaData {
         Default::default()
     }
     
+    /// Merge custom fields into this metadata
+    ///
+    /// Fields that already exist are deep-merged with `merge_json_values`,
+    /// with values from `other` taking precedence.
+    pub fn merge_custom(&mut self, other: &HashMap<String, serde_json::Value>) {
+        if !other.is_empty() {
+            self.mark_included();
+        }
+        for (key, value) in other {
+            let merged = match self.custom.get(key) {
+                Some(existing) => merge_json_values(existing, value),
+                None => value.clone(),
+            };
+            self.custom.insert(key.clone(), merged);
+        }
+    }
+    
+    /// Create placeholder metadata that is omitted from serialized output
+    ///
+    /// No UUID or timestamp is generated; the id is nil and the timestamp is
+    /// the Unix epoch. Setting a field through a method such as `add_field`
+    /// or `LogEntry::with_source` brings the metadata back into the output;
+    /// assigning a field directly does not.
+    pub fn omitted() -> Self {
+        Self {
+            id: Uuid::nil(),
+            timestamp: DateTime::UNIX_EPOCH,
+            received_at: None,
+            source: None,
+            line: None,
+            column: None,
+            function: None,
+            thread: None,
+            logger: None,
+            sequence: None,
+            uptime_ns: None,
+            ttl_seconds: None,
+            trace_flags: None,
+            custom: HashMap::new(),
+            omitted: true,
+        }
+    }
+    
+    /// Check if the metadata is omitted from serialized output
+    pub fn is_omitted(&self) -> bool {
+        self.omitted
+    }
+    
+    /// Include the metadata in serialized output again
+    pub(crate) fn mark_included(&mut self) {
+        self.omitted = false;
+    }
+    
+    /// Create new metadata with room for `custom_cap` custom fields
+    pub fn with_capacity(custom_cap: usize) -> Self {
+        Self {
+            custom: HashMap::with_capacity(custom_cap),
+            ..Default::default()
+        }
+    }
+    
+    /// Create new metadata with a time-ordered UUIDv7 id
+    ///
+    /// Ids generated this way sort by creation time, which gives better
+    /// index locality in log stores.
+    #[cfg(feature = "uuid-v7")]
+    pub fn with_uuid_v7() -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            ..Default::default()
+        }
+    }
+    
+    /// Record the current time as the moment the entry was received
+    ///
+    /// `timestamp` keeps the event time, so both can be shown side by side.
+    pub fn stamp_received(&mut self) {
+        self.mark_included();
+        self.received_at = Some(Utc::now());
+    }
+    
+    /// Set the hierarchical logger name, e.g. `com.app.db`
+    ///
+    /// Segments are separated by dots; `LoggerFilter` matches by prefix.
+    pub fn with_logger(mut self, name: impl Into<String>) -> Self {
+        self.mark_included();
+        self.logger = Some(name.into());
+        self
+    }
+    
+    /// Set the intended retention of the entry
+    ///
+    /// Stored with second precision; sub-second parts are dropped.
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.mark_included();
+        self.ttl_seconds = Some(ttl.as_secs());
+        self
+    }
+    
+    /// Check whether the entry's TTL has elapsed at `now`
+    ///
+    /// Entries without a TTL never expire.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        let Some(ttl) = self.ttl_seconds else {
+            return false;
+        };
+        
+        let ttl = chrono::Duration::try_seconds(i64::try_from(ttl).unwrap_or(i64::MAX))
+            .unwrap_or(chrono::Duration::MAX);
+        match self.timestamp.checked_add_signed(ttl) {
+            Some(expiry) => now >= expiry,
+            None => false,
+        }
+    }
+    
+    /// Check whether the upstream trace was sampled
+    ///
+    /// True when the sampled bit (the lowest trace flag) is set; false if
+    /// no trace flags are known.
+    pub fn is_sampled(&self) -> bool {
+        self.trace_flags.is_some_and(|flags| flags & 0x01 != 0)
+    }
+    
     /// Add a custom field to the metadata
     pub fn add_field<T>(&mut self, key: &str, value: T) -> Result<()>
     where
@@ -85,23 +360,51 @@ impl MetaData {
     {
         let value = serde_json::to_value(value)
             .map_err(Error::SerializationError)?;
+        self.mark_included();
         self.custom.insert(key.to_string(), value);
         Ok(())
     }
 }
 
+/// Key wrapping base64-encoded binary context values
+const BASE64_FIELD: &str = "_b64";
+
+/// Context field marking an entry trimmed by `LogEntry::to_json_capped`
+const TRUNCATED_FIELD: &str = "_truncated";
+
+/// Upper bound on the bytes the truncation marker adds, separator included
+const TRUNCATED_MARKER_LEN: usize = r#","_truncated":true"#.len();
+
 /// Core log entry structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     /// The primary log message
     pub message: String,
+    /// Optional long-form detail accompanying the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
     /// Log severity level
     pub level: LogLevel,
     /// Metadata about the log
+    #[serde(default = "MetaData::omitted", skip_serializing_if = "MetaData::is_omitted")]
     pub metadata: MetaData,
     /// Context fields for the log entry
     #[serde(default)]
     pub context: HashMap<String, serde_json::Value>,
+    /// Which source set each context field, when recorded
+    #[serde(rename = "_provenance", default, skip_serializing_if = "HashMap::is_empty")]
+    pub provenance: HashMap<String, String>,
+    /// Maximum number of context fields, if capped
+    ///
+    /// Not serialized: entries parsed from JSON start uncapped, while
+    /// in-place edits, `from_json_delta` and `aggregate` keep the cap.
+    #[serde(skip)]
+    pub(crate) max_context_fields: Option<usize>,
+    /// How context methods store NaN and infinite floats
+    ///
+    /// Not serialized; carried across the same paths as `max_context_fields`.
+    #[serde(skip)]
+    pub(crate) non_finite: NonFiniteMode,
 }
 
 impl LogEntry {
@@ -109,10 +412,177 @@ impl LogEntry {
     pub fn new(message: impl Into<String>, level: LogLevel) -> Self {
         Self {
             message: message.into(),
+            body: None,
             level,
             metadata: MetaData::default(),
             context: HashMap::new(),
+            provenance: HashMap::new(),
+            max_context_fields: None,
+            non_finite: NonFiniteMode::Null,
+        }
+    }
+    
+    /// Create a lightweight log entry without metadata
+    ///
+    /// Skips UUID and timestamp generation; the serialized entry has no
+    /// `metadata` object. Intended for very high-volume debug logs. Setting
+    /// metadata afterwards, e.g. with `with_source`, brings it back into the
+    /// output with a nil id and epoch timestamp.
+    pub fn lightweight(message: impl Into<String>, level: LogLevel) -> Self {
+        Self {
+            message: message.into(),
+            body: None,
+            level,
+            metadata: MetaData::omitted(),
+            context: HashMap::new(),
+            provenance: HashMap::new(),
+            max_context_fields: None,
+            non_finite: NonFiniteMode::Null,
+        }
+    }
+    
+    /// Drop the metadata so it is left out of serialized output
+    pub fn without_metadata(mut self) -> Self {
+        self.metadata = MetaData::omitted();
+        self
+    }
+    
+    /// Create a new log entry recording the caller's source location
+    ///
+    /// The file and line of the call site are captured automatically, so
+    /// there's no need to pass `file!()` and `line!()` to `with_source`.
+    #[track_caller]
+    pub fn here(message: impl Into<String>, level: LogLevel) -> Self {
+        let location = std::panic::Location::caller();
+        let mut entry = Self::new(message, level);
+        entry.metadata.source = Some(location.file().to_string());
+        entry.metadata.line = Some(location.line());
+        entry
+    }
+    
+    /// Create a new log entry with room for `context_cap` context fields
+    ///
+    /// Avoids rehashing when the number of fields to add is known upfront.
+    pub fn with_capacity(message: impl Into<String>, level: LogLevel, context_cap: usize) -> Self {
+        Self {
+            context: HashMap::with_capacity(context_cap),
+            ..Self::new(message, level)
+        }
+    }
+    
+    /// Create a log entry from all of its common parts in one call
+    pub fn from_parts(
+        message: impl Into<String>,
+        level: LogLevel,
+        source: Option<(&str, u32)>,
+        context: HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let mut entry = Self::new(message, level);
+        if let Some((file, line)) = source {
+            entry.metadata.source = Some(file.to_string());
+            entry.metadata.line = Some(line);
+        }
+        entry.context = context;
+        entry
+    }
+    
+    /// Parse an entry from untrusted JSON without panicking
+    ///
+    /// Input longer than `max_bytes` or nested deeper than `max_depth` is
+    /// rejected before deserialization. This is the entry point to use for
+    /// fuzzing and for input from untrusted sources.
+    pub fn from_json_safe(input: &str, max_depth: usize, max_bytes: usize) -> Result<Self> {
+        if input.len() > max_bytes {
+            return Err(Error::LoggingError(format!(
+                "Input of {} bytes exceeds the {} byte limit", input.len(), max_bytes
+            )));
+        }
+        if json_depth_exceeds(input, max_depth) {
+            return Err(Error::LoggingError(format!(
+                "Input exceeds the maximum nesting depth of {}", max_depth
+            )));
+        }
+        
+        serde_json::from_str(input).map_err(Error::SerializationError)
+    }
+    
+    /// Create a log entry with the given context fields
+    ///
+    /// Fields are added in order with `add_context`, so a later pair with
+    /// the same key replaces an earlier one.
+    pub fn with_fields(
+        message: impl Into<String>,
+        level: LogLevel,
+        fields: &[(&str, serde_json::Value)],
+    ) -> Result<Self> {
+        let mut entry = Self::with_capacity(message, level, fields.len());
+        for (key, value) in fields {
+            entry.add_context(*key, value)?;
+        }
+        Ok(entry)
+    }
+    
+    /// Summarize entries sharing a message into a single entry
+    ///
+    /// Fails if `entries` is empty or the messages differ; use
+    /// `aggregate_as` to summarize mixed messages. See `aggregate_as` for
+    /// how the summary is built.
+    pub fn aggregate(entries: &[LogEntry]) -> Result<Self> {
+        let first = entries.first().ok_or_else(|| {
+            Error::LoggingError("Cannot aggregate an empty set of entries".to_string())
+        })?;
+        if let Some(other) = entries.iter().find(|entry| entry.message != first.message) {
+            return Err(Error::LoggingError(format!(
+                "Cannot aggregate differing messages '{}' and '{}'; provide a summary message",
+                first.message, other.message
+            )));
+        }
+        Self::aggregate_as(entries, first.message.clone())
+    }
+    
+    /// Summarize entries into a single entry with the given message
+    ///
+    /// The summary takes the highest level and deep-merges all contexts,
+    /// later entries winning on conflicts. Its timestamp is the earliest
+    /// one; `metadata.custom` records `count`, `first_timestamp` and
+    /// `last_timestamp`.
+    pub fn aggregate_as(entries: &[LogEntry], message: impl Into<String>) -> Result<Self> {
+        let first = entries.first().ok_or_else(|| {
+            Error::LoggingError("Cannot aggregate an empty set of entries".to_string())
+        })?;
+        
+        let mut earliest = first.metadata.timestamp;
+        let mut latest = first.metadata.timestamp;
+        let mut level = first.level;
+        let mut context = serde_json::Value::Object(serde_json::Map::new());
+        for entry in entries {
+            earliest = earliest.min(entry.metadata.timestamp);
+            latest = latest.max(entry.metadata.timestamp);
+            level = level.max(entry.level);
+            context = merge_json_values(&context, &serde_json::to_value(&entry.context)?);
         }
+        
+        let mut summary = Self::new(message, level);
+        summary.context = serde_json::from_value(context)?;
+        summary.metadata.timestamp = earliest;
+        summary.max_context_fields = first.max_context_fields;
+        summary.non_finite = first.non_finite;
+        summary.metadata.add_field("count", entries.len())?;
+        summary.metadata.add_field("first_timestamp", format_timestamp(&earliest))?;
+        summary.metadata.add_field("last_timestamp", format_timestamp(&latest))?;
+        Ok(summary)
+    }
+    
+    /// Replace the entry's level
+    pub fn set_level(&mut self, level: LogLevel) -> &mut Self {
+        self.level = level;
+        self
+    }
+    
+    /// Replace the entry's message
+    pub fn set_message(&mut self, message: impl Into<String>) -> &mut Self {
+        self.message = message.into();
+        self
     }
     
     /// Add context to the log entry
@@ -120,34 +590,882 @@ impl LogEntry {
     where
         T: Serialize,
     {
-        let value = serde_json::to_value(value)
-            .map_err(Error::SerializationError)?;
-        self.context.insert(key.into(), value);
+        let key = key.into();
+        self.check_context_capacity([key.as_str()])?;
+        let value = to_value_finite(&value, self.non_finite)?;
+        self.context.insert(key, value);
+        Ok(self)
+    }
+    
+    /// Add context with the key passed through `util::sanitize_field_name`
+    ///
+    /// Dots, spaces and other path-breaking characters become `_`, so the
+    /// field stays addressable with `get_nested_value` and `flatten_json`.
+    pub fn add_context_sanitized<T>(&mut self, key: &str, value: T) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        self.add_context(sanitize_field_name(key), value)
+    }
+    
+    /// Cap the number of context fields
+    ///
+    /// Adding a new key beyond the cap, through `add_context` or any other
+    /// context method, fails with `Error::LoggingError`; replacing an
+    /// existing key is always allowed. Guards against loops that
+    /// accidentally grow the context without bound.
+    pub fn with_max_context_fields(mut self, max: usize) -> Self {
+        self.max_context_fields = Some(max);
+        self
+    }
+    
+    /// Maximum number of context fields, if capped
+    pub fn max_context_fields(&self) -> Option<usize> {
+        self.max_context_fields
+    }
+    
+    /// Choose how context methods store NaN and infinite floats
+    ///
+    /// By default they become `null` like in plain `serde_json`, which hides
+    /// that the value was bad. `NonFiniteMode::Sentinel` keeps them as
+    /// `"NaN"`/`"Infinity"` strings, `NonFiniteMode::Reject` fails the add.
+    pub fn with_non_finite(mut self, mode: NonFiniteMode) -> Self {
+        self.non_finite = mode;
+        self
+    }
+    
+    /// How context methods store NaN and infinite floats
+    pub fn non_finite(&self) -> NonFiniteMode {
+        self.non_finite
+    }
+    
+    /// Fail if adding `keys` would exceed `max_context_fields`
+    ///
+    /// Checked before anything is inserted, so a rejected batch leaves the
+    /// context unchanged.
+    fn check_context_capacity<'k>(&self, keys: impl IntoIterator<Item = &'k str>) -> Result<()> {
+        let Some(max) = self.max_context_fields else {
+            return Ok(());
+        };
+        
+        let mut new_keys: Vec<&str> = Vec::new();
+        for key in keys {
+            if !self.context.contains_key(key) && !new_keys.contains(&key) {
+                new_keys.push(key);
+            }
+        }
+        
+        let room = max.saturating_sub(self.context.len());
+        match new_keys.get(room) {
+            Some(key) => Err(Error::LoggingError(format!(
+                "Cannot add context field '{}': limit of {} fields reached", key, max
+            ))),
+            None => Ok(()),
+        }
+    }
+    
+    /// Preview up to `n` scalar context fields for compact list views
+    ///
+    /// Fields are ordered by key; arrays and objects are skipped and scalar
+    /// values are converted to strings.
+    pub fn context_preview(&self, n: usize) -> Vec<(String, String)> {
+        let mut keys: Vec<&String> = self.context.keys().collect();
+        keys.sort();
+        
+        keys.into_iter()
+            .filter_map(|key| {
+                let value = match &self.context[key] {
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => return None,
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                Some((key.clone(), value))
+            })
+            .take(n)
+            .collect()
+    }
+    
+    /// Pick a shard for this entry from the named context value
+    ///
+    /// Uses `simple_hash` of the value (strings hash their raw text), so the
+    /// same value always maps to the same shard. Entries without the key,
+    /// and a `shards` count of zero, map to shard 0.
+    pub fn partition(&self, key: &str, shards: usize) -> usize {
+        if shards == 0 {
+            return 0;
+        }
+        
+        let hash = match self.context.get(key) {
+            Some(serde_json::Value::String(s)) => simple_hash(s),
+            Some(other) => simple_hash(&other.to_string()),
+            None => return 0,
+        };
+        (hash % shards as u64) as usize
+    }
+    
+    /// Add binary data to the context as base64
+    ///
+    /// The value is stored as `{"_b64": "<encoded>"}`; read it back with
+    /// `context_bytes`.
+    pub fn add_context_bytes(&mut self, key: impl Into<String>, bytes: &[u8]) -> Result<&mut Self> {
+        let key = key.into();
+        self.check_context_capacity([key.as_str()])?;
+        let mut wrapper = serde_json::Map::new();
+        wrapper.insert(BASE64_FIELD.to_string(), serde_json::Value::String(BASE64.encode(bytes)));
+        self.context.insert(key, serde_json::Value::Object(wrapper));
+        Ok(self)
+    }
+    
+    /// Decode binary data stored with `add_context_bytes`
+    ///
+    /// Returns `None` if the key is missing or doesn't hold valid base64 data.
+    pub fn context_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        let encoded = self.context.get(key)?.get(BASE64_FIELD)?.as_str()?;
+        BASE64.decode(encoded).ok()
+    }
+    
+    /// Add context and record which source set it
+    ///
+    /// The provenance map is only serialized once a field has been added
+    /// this way, so normal output is unaffected.
+    pub fn add_context_from<T>(
+        &mut self,
+        source: &str,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        let key = key.into();
+        self.add_context(key.clone(), value)?;
+        self.provenance.insert(key, source.to_string());
+        Ok(self)
+    }
+    
+    /// Add context using a custom formatter from the registry
+    ///
+    /// Values of types without a registered formatter are serialized as
+    /// with `add_context`, honouring the entry's `NonFiniteMode`.
+    pub fn add_context_custom<T>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+        registry: &ValueFormatterRegistry,
+    ) -> Result<&mut Self>
+    where
+        T: Serialize + 'static,
+    {
+        let key = key.into();
+        self.check_context_capacity([key.as_str()])?;
+        let value = if registry.contains::<T>() {
+            registry.format(&value)?
+        } else {
+            to_value_finite(&value, self.non_finite)?
+        };
+        self.context.insert(key, value);
+        Ok(self)
+    }
+    
+    /// Open a scope whose context changes are undone when it ends
+    ///
+    /// Fields added or changed through the returned guard are restored to
+    /// their previous state (or removed) when the guard is dropped, much
+    /// like span-scoped fields.
+    pub fn context_scope(&mut self) -> ContextScope<'_> {
+        ContextScope {
+            entry: self,
+            saved: HashMap::new(),
+        }
+    }
+    
+    /// Merge a JSON object into the context
+    ///
+    /// Each key of the object is inserted into the context. Keys that already
+    /// exist are deep-merged with `merge_json_values`. Fails if `value` is not
+    /// a JSON object.
+    pub fn extend_context(&mut self, value: serde_json::Value) -> Result<&mut Self> {
+        let map = match value {
+            serde_json::Value::Object(map) => map,
+            other => {
+                return Err(Error::LoggingError(format!(
+                    "Cannot extend context with non-object JSON value: {}", other
+                )));
+            }
+        };
+        self.check_context_capacity(map.keys().map(String::as_str))?;
+        
+        for (key, value) in map {
+            let merged = match self.context.get(&key) {
+                Some(existing) => merge_json_values(existing, &value),
+                None => value,
+            };
+            self.context.insert(key, merged);
+        }
         Ok(self)
     }
     
+    /// Add each field of a serializable object as `namespace.field` context
+    ///
+    /// Keys are joined with `nested_field_path`, which sanitizes both
+    /// parts. Values that don't serialize to a JSON object are rejected.
+    pub fn add_namespaced_context<T: Serialize>(&mut self, namespace: &str, value: &T) -> Result<&mut Self> {
+        let value = to_value_finite(value, self.non_finite)?;
+        let serde_json::Value::Object(map) = value else {
+            return Err(Error::LoggingError(format!(
+                "Cannot namespace non-object JSON value: {}", value
+            )));
+        };
+        
+        let fields: Vec<(String, serde_json::Value)> = map
+            .into_iter()
+            .map(|(key, value)| (nested_field_path(namespace, &key), value))
+            .collect();
+        self.check_context_capacity(fields.iter().map(|(key, _)| key.as_str()))?;
+        
+        for (key, value) in fields {
+            self.context.insert(key, value);
+        }
+        Ok(self)
+    }
+    
+    /// Remove repeated scalar elements from array-valued context fields
+    ///
+    /// Only the first occurrence of each string, number, boolean or null is
+    /// kept, preserving order; nested arrays and objects are left alone.
+    /// Returns the number of elements removed.
+    pub fn dedup_context_arrays(&mut self) -> usize {
+        let mut removed = 0;
+        for value in self.context.values_mut() {
+            let serde_json::Value::Array(items) = value else {
+                continue;
+            };
+            
+            let before = items.len();
+            let mut seen: Vec<serde_json::Value> = Vec::new();
+            items.retain(|item| {
+                if item.is_array() || item.is_object() {
+                    return true;
+                }
+                if seen.contains(item) {
+                    return false;
+                }
+                seen.push(item.clone());
+                true
+            });
+            removed += before - items.len();
+        }
+        removed
+    }
+    
+    /// Compact context strings that hold JSON objects or arrays
+    ///
+    /// Catches pretty-printed payloads such as captured request bodies.
+    /// `EmbeddedJson::Minify` rewrites them without whitespace (object keys
+    /// come out sorted); `EmbeddedJson::Parse` replaces them with the parsed
+    /// value. Other strings, including invalid JSON and bare scalars like
+    /// `"42"`, are left alone. Returns the number of fields changed.
+    pub fn compact_embedded_json(&mut self, mode: EmbeddedJson) -> usize {
+        let mut changed = 0;
+        for value in self.context.values_mut() {
+            let serde_json::Value::String(text) = value else {
+                continue;
+            };
+            
+            let trimmed = text.trim_start();
+            if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+                continue;
+            }
+            let Ok(parsed) = serde_json::from_str::<serde_json::Value>(text) else {
+                continue;
+            };
+            
+            match mode {
+                EmbeddedJson::Minify => {
+                    let minified = parsed.to_string();
+                    if minified == *text {
+                        continue;
+                    }
+                    *text = minified;
+                },
+                EmbeddedJson::Parse => *value = parsed,
+            }
+            changed += 1;
+        }
+        changed
+    }
+    
+    /// Merge custom metadata fields into the entry
+    ///
+    /// See `MetaData::merge_custom`.
+    pub fn merge_metadata_custom(&mut self, other: &HashMap<String, serde_json::Value>) -> &mut Self {
+        self.metadata.merge_custom(other);
+        self
+    }
+    
+    /// Flag the entry if its timestamp is ahead of now by more than `tolerance`
+    ///
+    /// Adds a `clock_skew_detected: true` context field when the timestamp
+    /// is in the future, which usually indicates a bad system clock. Returns
+    /// whether the entry was flagged.
+    pub fn flag_future_timestamp(&mut self, tolerance: std::time::Duration) -> bool {
+        let tolerance = chrono::Duration::from_std(tolerance).unwrap_or(chrono::Duration::MAX);
+        let now = Utc::now();
+        let limit = now.checked_add_signed(tolerance).unwrap_or(DateTime::<Utc>::MAX_UTC);
+        
+        if self.metadata.timestamp > limit {
+            self.context.insert("clock_skew_detected".to_string(), serde_json::Value::Bool(true));
+            true
+        } else {
+            false
+        }
+    }
+    
     /// Add source location information
     pub fn with_source(mut self, file: &str, line: u32) -> Self {
+        self.metadata.mark_included();
         self.metadata.source = Some(file.to_string());
         self.metadata.line = Some(line);
         self
     }
     
+    /// Add full source location information
+    pub fn with_location(
+        mut self,
+        file: &str,
+        line: u32,
+        column: Option<u32>,
+        function: Option<&str>,
+    ) -> Self {
+        self.metadata.mark_included();
+        self.metadata.source = Some(file.to_string());
+        self.metadata.line = Some(line);
+        self.metadata.column = column;
+        self.metadata.function = function.map(|f| f.to_string());
+        self
+    }
+    
+    /// Attach the current stack as structured frames
+    ///
+    /// Frames are stored as an array under the `backtrace` context field.
+    /// In release builds the array is empty (see `util::capture_frames`).
+    pub fn with_structured_backtrace(mut self) -> Self {
+        let frames = serde_json::to_value(capture_frames())
+            .unwrap_or_else(|_| serde_json::Value::Array(Vec::new()));
+        self.context.insert("backtrace".to_string(), frames);
+        self
+    }
+    
+    /// Record how long an operation took as a context field
+    pub fn with_elapsed(mut self, elapsed: std::time::Duration, format: DurationFormat) -> Self {
+        let ms = i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX);
+        match format {
+            DurationFormat::Millis => {
+                self.context.insert("elapsed_ms".to_string(), ms.into());
+            },
+            DurationFormat::Iso8601 => {
+                self.context.insert("elapsed".to_string(), format_iso_duration(ms).into());
+            },
+        }
+        self
+    }
+    
+    /// Mark the entry as a completed operation
+    ///
+    /// Stamps `span.duration_ms` and `span.status` context fields. An error
+    /// status raises the level to at least `Warn`.
+    pub fn as_span(mut self, duration: std::time::Duration, status: SpanStatus) -> Self {
+        let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self.context.insert(nested_field_path("span", "duration_ms"), duration_ms.into());
+        self.context.insert(
+            nested_field_path("span", "status"),
+            serde_json::to_value(status).unwrap_or(serde_json::Value::Null),
+        );
+        
+        if status == SpanStatus::Error {
+            self.level = self.level.max(LogLevel::Warn);
+        }
+        self
+    }
+    
+    /// Attach the trace context from a W3C `traceparent` header
+    ///
+    /// The header looks like `00-<trace id>-<parent span id>-<flags>`. The
+    /// trace and span ids become the `trace_id` and `span_id` context fields
+    /// (so consistent sampling groups by trace) and the flags are stored in
+    /// `metadata.trace_flags`.
+    pub fn with_trace_context(mut self, traceparent: &str) -> Result<Self> {
+        let invalid = || Error::LoggingError(format!("Invalid traceparent '{}'", traceparent));
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        };
+        
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        let [version, trace_id, span_id, flags, ..] = parts[..] else {
+            return Err(invalid());
+        };
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.len() != 4) {
+            return Err(invalid());
+        }
+        if !is_hex(trace_id, 32) || trace_id.bytes().all(|b| b == b'0') {
+            return Err(invalid());
+        }
+        if !is_hex(span_id, 16) || span_id.bytes().all(|b| b == b'0') {
+            return Err(invalid());
+        }
+        if !is_hex(flags, 2) {
+            return Err(invalid());
+        }
+        
+        self.context.insert(TRACE_ID_FIELD.to_string(), trace_id.into());
+        self.context.insert("span_id".to_string(), span_id.into());
+        self.metadata.mark_included();
+        self.metadata.trace_flags = u8::from_str_radix(flags, 16).ok();
+        Ok(self)
+    }
+    
+    /// Record active experiment variants under the `experiments` context object
+    ///
+    /// Maps experiment names to variants. Experiments already recorded on the
+    /// entry are kept unless overridden by name.
+    pub fn with_experiments(mut self, experiments: &HashMap<String, String>) -> Self {
+        let field = self.context
+            .entry(EXPERIMENTS_FIELD.to_string())
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if !field.is_object() {
+            *field = serde_json::Value::Object(serde_json::Map::new());
+        }
+        
+        if let Some(map) = field.as_object_mut() {
+            for (name, variant) in experiments {
+                map.insert(name.clone(), serde_json::Value::String(variant.clone()));
+            }
+        }
+        self
+    }
+    
+    /// Check whether an experiment was recorded with `with_experiments`
+    pub fn has_experiment(&self, name: &str) -> bool {
+        self.context
+            .get(EXPERIMENTS_FIELD)
+            .and_then(|experiments| experiments.get(name))
+            .is_some()
+    }
+    
+    /// Attach a snapshot of counters and gauges under the `metrics` context field
+    ///
+    /// JSON has no representation for NaN or infinity, so non-finite values
+    /// are stored as `null`; the key stays visible to show the metric was read.
+    pub fn with_metrics(mut self, metrics: &HashMap<String, f64>) -> Self {
+        let field = self.context
+            .entry(METRICS_FIELD.to_string())
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if !field.is_object() {
+            *field = serde_json::Value::Object(serde_json::Map::new());
+        }
+        
+        if let Some(map) = field.as_object_mut() {
+            for (name, value) in metrics {
+                let value = serde_json::Number::from_f64(*value)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or(serde_json::Value::Null);
+                map.insert(name.clone(), value);
+            }
+        }
+        self
+    }
+    
+    /// Set the intended retention of the entry; see `MetaData::with_ttl`
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.metadata.mark_included();
+        self.metadata.ttl_seconds = Some(ttl.as_secs());
+        self
+    }
+    
+    /// Set the logger name of the entry; see `MetaData::with_logger`
+    pub fn with_logger(mut self, name: impl Into<String>) -> Self {
+        self.metadata.mark_included();
+        self.metadata.logger = Some(name.into());
+        self
+    }
+    
+    /// Stamp the entry with monotonic nanoseconds since process start
+    ///
+    /// Unlike the wall-clock timestamp this can't jump, so it orders entries
+    /// correctly on devices whose real-time clock is unset. The start point
+    /// is captured the first time any entry is stamped.
+    pub fn with_uptime(mut self) -> Self {
+        let start = PROCESS_START.get_or_init(Instant::now);
+        let uptime = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.metadata.mark_included();
+        self.metadata.uptime_ns = Some(uptime);
+        self
+    }
+    
+    /// Attach a long-form body to the entry
+    ///
+    /// The message stays a short summary; formatters such as GELF map the
+    /// body to their full-message field.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+    
     /// Add thread information
     pub fn with_thread(mut self, thread_id: impl Into<String>) -> Self {
+        self.metadata.mark_included();
         self.metadata.thread = Some(thread_id.into());
         self
     }
     
+    /// Stamp the entry with the next process-wide sequence number
+    ///
+    /// Sequence numbers increase monotonically, so they break ties between
+    /// entries created within the same timestamp resolution.
+    pub fn with_sequence(mut self) -> Self {
+        self.metadata.mark_included();
+        self.metadata.sequence = Some(NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed));
+        self
+    }
+    
+    /// Flatten the entry into `(key, value)` pairs for tabular export
+    ///
+    /// `message`, `level` and `timestamp` come first, followed by metadata
+    /// and context flattened with `flatten_json` under `metadata.` and
+    /// `context.` prefixes and sorted by key. Strings are emitted as-is;
+    /// other values are JSON-stringified.
+    pub fn to_flat_pairs(&self) -> Vec<(String, String)> {
+        let timestamp = if self.metadata.is_omitted() {
+            String::new()
+        } else {
+            format_timestamp(&self.metadata.timestamp)
+        };
+        let mut pairs = vec![
+            ("message".to_string(), self.message.clone()),
+            ("level".to_string(), self.level.to_string()),
+            ("timestamp".to_string(), timestamp),
+        ];
+        
+        let mut rest = Vec::new();
+        if !self.metadata.is_omitted() {
+            if let Ok(mut metadata) = serde_json::to_value(&self.metadata) {
+                if let Some(map) = metadata.as_object_mut() {
+                    map.remove("timestamp");
+                }
+                rest.extend(flatten_json(&metadata, "metadata"));
+            }
+        }
+        for (key, value) in &self.context {
+            rest.extend(flatten_json(value, &format!("context.{}", key)));
+        }
+        rest.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        pairs.extend(rest.into_iter().map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (key, value)
+        }));
+        pairs
+    }
+    
+    /// View the entry for `{:?}`/`{}` printing with sensitive values masked
+    ///
+    /// Keys are matched case-insensitively at any depth of the context and
+    /// custom metadata, as with `Redactor`.
+    pub fn redacted_debug<I, S>(&self, keys: I) -> RedactedDebug<'_>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        RedactedDebug::new(self, Redactor::new(keys))
+    }
+    
+    /// Render the `Display` line prefixed with the level's glyph
+    ///
+    /// For console output; `glyphs` supplies any per-level overrides.
+    pub fn display_line(&self, glyphs: &LevelGlyphs) -> String {
+        format!("{} {}", glyphs.get(self.level), self)
+    }
+    
+    /// Check whether two entries carry the same content
+    ///
+    /// Compares message, level, context and the stable parts of the metadata,
+    /// ignoring the volatile id, timestamp, sequence number and uptime.
+    pub fn semantically_eq(&self, other: &LogEntry) -> bool {
+        self.message == other.message
+            && self.body == other.body
+            && self.level == other.level
+            && self.context == other.context
+            && self.metadata.source == other.metadata.source
+            && self.metadata.line == other.metadata.line
+            && self.metadata.column == other.metadata.column
+            && self.metadata.function == other.metadata.function
+            && self.metadata.thread == other.metadata.thread
+            && self.metadata.logger == other.metadata.logger
+            && self.metadata.ttl_seconds == other.metadata.ttl_seconds
+            && self.metadata.trace_flags == other.metadata.trace_flags
+            && self.metadata.custom == other.metadata.custom
+    }
+    
     /// Convert to JSON string
     pub fn to_json(&self) -> Result<String> {
         serde_json::to_string(self).map_err(Error::SerializationError)
     }
     
+    /// Convert to a JSON value
+    pub fn to_value(&self) -> Result<serde_json::Value> {
+        serde_json::to_value(self).map_err(Error::SerializationError)
+    }
+    
     /// Convert to pretty-printed JSON string
     pub fn to_pretty_json(&self) -> Result<String> {
         serde_json::to_string_pretty(self).map_err(Error::SerializationError)
     }
+    
+    /// Write compact JSON straight to `writer` without building a `String`
+    pub fn to_writer<W: std::io::Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer(writer, self).map_err(writer_error)
+    }
+    
+    /// Write pretty-printed JSON straight to `writer`
+    pub fn to_writer_pretty<W: std::io::Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(writer_error)
+    }
+    
+    /// Serialize only the fields that differ from `template`
+    ///
+    /// The receiver rebuilds the entry with `from_json_delta`. Removed
+    /// fields are listed under `util::DIFF_REMOVED_KEY`; an identical entry
+    /// produces `{}`.
+    pub fn to_json_delta(&self, template: &LogEntry) -> Result<String> {
+        let base = serde_json::to_value(template).map_err(Error::SerializationError)?;
+        let target = serde_json::to_value(self).map_err(Error::SerializationError)?;
+        
+        let delta = json_diff(&base, &target)
+            .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new()));
+        serde_json::to_string(&delta).map_err(Error::SerializationError)
+    }
+    
+    /// Rebuild an entry from a `to_json_delta` result and its template
+    ///
+    /// The rebuilt entry keeps the template's context cap and non-finite
+    /// mode, which are not serialized.
+    pub fn from_json_delta(template: &LogEntry, delta: &str) -> Result<Self> {
+        let base = serde_json::to_value(template).map_err(Error::SerializationError)?;
+        let delta: serde_json::Value = serde_json::from_str(delta).map_err(Error::SerializationError)?;
+        
+        let mut entry: LogEntry = serde_json::from_value(apply_json_diff(&base, &delta))
+            .map_err(Error::SerializationError)?;
+        entry.max_context_fields = template.max_context_fields;
+        entry.non_finite = template.non_finite;
+        Ok(entry)
+    }
+    
+    /// Convert to a YAML string
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self)
+            .map_err(|e| Error::FormatterError(format!("YAML serialization failed: {}", e)))
+    }
+    
+    /// Convert to a JSON string of at most `max_bytes`
+    ///
+    /// If the entry is over budget, the largest context values are dropped
+    /// one at a time and a reserved `_truncated: true` context field is
+    /// added once trimming is done. Fails if the entry doesn't fit even
+    /// without any context.
+    pub fn to_json_capped(&self, max_bytes: usize) -> Result<String> {
+        let json = self.to_json()?;
+        if json.len() <= max_bytes {
+            return Ok(json);
+        }
+        
+        let mut sizes = Vec::with_capacity(self.context.len());
+        for (key, value) in &self.context {
+            if key == TRUNCATED_FIELD {
+                continue;
+            }
+            let size = serde_json::to_string(value).map_err(Error::SerializationError)?.len();
+            sizes.push((size + key.len(), key.clone()));
+        }
+        sizes.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        
+        let mut trimmed = self.clone();
+        trimmed.context.remove(TRUNCATED_FIELD);
+        for (_, key) in sizes {
+            trimmed.context.remove(&key);
+            trimmed.provenance.remove(&key);
+            
+            if trimmed.estimated_json_size() + TRUNCATED_MARKER_LEN <= max_bytes {
+                trimmed.context.insert(TRUNCATED_FIELD.to_string(), serde_json::Value::Bool(true));
+                return trimmed.to_json();
+            }
+        }
+        
+        Err(Error::LoggingError(format!(
+            "Log entry exceeds the {} byte budget even without context", max_bytes
+        )))
+    }
+    
+    /// Size in bytes of the compact JSON serialization
+    ///
+    /// Counts the serialized bytes without building the output string.
+    /// Returns 0 if the entry can't be serialized.
+    pub fn estimated_json_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        match serde_json::to_writer(&mut counter, self) {
+            Ok(()) => counter.0,
+            Err(_) => 0,
+        }
+    }
+    
+    /// Convert to canonical JSON suitable for hashing or signing
+    ///
+    /// Keys are sorted, whitespace is omitted and numbers are normalized, so
+    /// logically-equal entries produce byte-identical output.
+    pub fn to_canonical_json(&self) -> Result<String> {
+        let value = serde_json::to_value(self).map_err(Error::SerializationError)?;
+        Ok(canonical_json(&value))
+    }
+}
+
+/// How `LogEntry::with_elapsed` records a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationFormat {
+    /// Integer milliseconds in an `elapsed_ms` field
+    #[default]
+    Millis,
+    /// ISO 8601 duration string (e.g. `PT1M5S`) in an `elapsed` field
+    Iso8601,
+}
+
+/// How `LogEntry::compact_embedded_json` treats JSON held in strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddedJson {
+    /// Keep a string, re-serialized without whitespace
+    #[default]
+    Minify,
+    /// Replace the string with the parsed JSON value
+    Parse,
+}
+
+/// Outcome of a completed operation logged with `LogEntry::as_span`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanStatus {
+    /// The operation succeeded
+    Ok,
+    /// The operation failed
+    Error,
+}
+
+/// Guard returned by `LogEntry::context_scope`
+///
+/// Records the previous value of every key it touches and restores them
+/// on drop.
+pub struct ContextScope<'a> {
+    entry: &'a mut LogEntry,
+    saved: HashMap<String, Option<serde_json::Value>>,
+}
+
+impl ContextScope<'_> {
+    /// Add or replace a context field for the duration of the scope
+    pub fn add_context<T>(&mut self, key: impl Into<String>, value: T) -> Result<&mut Self>
+    where
+        T: Serialize,
+    {
+        let key = key.into();
+        self.entry.check_context_capacity([key.as_str()])?;
+        let value = to_value_finite(&value, self.entry.non_finite)?;
+        let previous = self.entry.context.insert(key.clone(), value);
+        self.saved.entry(key).or_insert(previous);
+        Ok(self)
+    }
+    
+    /// Remove a context field for the duration of the scope
+    pub fn remove_context(&mut self, key: &str) -> Option<serde_json::Value> {
+        let previous = self.entry.context.remove(key);
+        self.saved.entry(key.to_string()).or_insert_with(|| previous.clone());
+        previous
+    }
+}
+
+impl std::ops::Deref for ContextScope<'_> {
+    type Target = LogEntry;
+    
+    fn deref(&self) -> &LogEntry {
+        self.entry
+    }
+}
+
+impl Drop for ContextScope<'_> {
+    fn drop(&mut self) {
+        for (key, previous) in self.saved.drain() {
+            match previous {
+                Some(value) => {
+                    self.entry.context.insert(key, value);
+                },
+                None => {
+                    self.entry.context.remove(&key);
+                },
+            }
+        }
+    }
+}
+
+/// Split `serde_json` writer errors into I/O and serialization failures
+fn writer_error(err: serde_json::Error) -> Error {
+    if err.is_io() {
+        Error::IoError(err.into())
+    } else {
+        Error::SerializationError(err)
+    }
+}
+
+/// Writer that discards bytes, only counting them
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {}",
+            self.level.to_string().to_uppercase(),
+            format_timestamp(&self.metadata.timestamp),
+            self.message
+        )?;
+        
+        if !self.context.is_empty() {
+            let mut keys: Vec<&String> = self.context.keys().collect();
+            keys.sort();
+            
+            write!(f, " {{")?;
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                match &self.context[key] {
+                    serde_json::Value::String(s) => write!(f, "{}={}", key, s)?,
+                    other => write!(f, "{}={}", key, other)?,
+                }
+            }
+            write!(f, "}}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Trait for types that can be serialized to JSON
@@ -177,4 +1495,871 @@ where
     fn to_value(&self) -> Result<serde_json::Value> {
         serde_json::to_value(self).map_err(Error::SerializationError)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_semantically_eq() {
+        let mut a = LogEntry::new("User logged in", LogLevel::Info);
+        a.add_context("user_id", "12345").unwrap();
+        let mut b = LogEntry::new("User logged in", LogLevel::Info);
+        b.add_context("user_id", "12345").unwrap();
+        b.metadata.timestamp = a.metadata.timestamp + chrono::Duration::seconds(5);
+        
+        assert_ne!(a.metadata.id, b.metadata.id);
+        assert!(a.semantically_eq(&b));
+        
+        let mut c = LogEntry::new("User logged out", LogLevel::Info);
+        c.add_context("user_id", "12345").unwrap();
+        assert!(!a.semantically_eq(&c));
+    }
+    
+    #[test]
+    fn test_location_fields_serialized_only_when_set() {
+        let entry = LogEntry::new("Plain", LogLevel::Info).with_source("src/main.rs", 10);
+        let json = entry.to_value().unwrap();
+        assert_eq!(json["metadata"]["line"], 10);
+        assert!(json["metadata"].get("column").is_none());
+        assert!(json["metadata"].get("function").is_none());
+        
+        let entry = LogEntry::new("Located", LogLevel::Info)
+            .with_location("src/main.rs", 10, Some(5), Some("main"));
+        let json = entry.to_value().unwrap();
+        assert_eq!(json["metadata"]["source"], "src/main.rs");
+        assert_eq!(json["metadata"]["column"], 5);
+        assert_eq!(json["metadata"]["function"], "main");
+    }
+    
+    #[test]
+    fn test_canonical_json_is_deterministic() {
+        let mut a = LogEntry::new("Payment processed", LogLevel::Info);
+        a.add_context("amount", 10.0).unwrap();
+        a.add_context("currency", "EUR").unwrap();
+        a.add_context("customer", serde_json::json!({"tier": "gold", "id": 7})).unwrap();
+        
+        let mut b = a.clone();
+        b.context.clear();
+        b.add_context("customer", serde_json::json!({"id": 7, "tier": "gold"})).unwrap();
+        b.add_context("currency", "EUR").unwrap();
+        b.add_context("amount", 10).unwrap();
+        
+        let canonical = a.to_canonical_json().unwrap();
+        assert_eq!(canonical, b.to_canonical_json().unwrap());
+        assert!(!canonical.contains(": ") && !canonical.contains('\n'));
+        assert!(canonical.contains(r#""amount":10,"#));
+    }
+    
+    #[cfg(feature = "uuid-v7")]
+    #[test]
+    fn test_uuid_v7_ids_sort_by_creation() {
+        let ids: Vec<Uuid> = (0..100).map(|_| MetaData::with_uuid_v7().id).collect();
+        
+        assert!(ids.iter().all(|id| id.get_version_num() == 7));
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(ids, sorted);
+    }
+    
+    #[test]
+    fn test_from_parts() {
+        let mut context = HashMap::new();
+        context.insert("target".to_string(), serde_json::json!("api"));
+        
+        let entry = LogEntry::from_parts(
+            "Request received",
+            LogLevel::Debug,
+            Some(("src/api.rs", 42)),
+            context,
+        );
+        
+        assert_eq!(entry.message, "Request received");
+        assert_eq!(entry.level, LogLevel::Debug);
+        assert_eq!(entry.metadata.source.as_deref(), Some("src/api.rs"));
+        assert_eq!(entry.metadata.line, Some(42));
+        assert_eq!(entry.context["target"], serde_json::json!("api"));
+        
+        let entry = LogEntry::from_parts("No source", LogLevel::Info, None, HashMap::new());
+        assert!(entry.metadata.source.is_none());
+        assert!(entry.context.is_empty());
+    }
+    
+    #[test]
+    fn test_to_json_capped() {
+        let mut entry = LogEntry::new("Upload finished", LogLevel::Info);
+        entry.add_context("file", "report.pdf").unwrap();
+        entry.add_context("payload", "x".repeat(500)).unwrap();
+        
+        // Under budget: output is unchanged
+        let full = entry.to_json().unwrap();
+        assert_eq!(entry.to_json_capped(full.len()).unwrap(), full);
+        
+        // Over budget: the largest value is dropped first
+        let capped = entry.to_json_capped(full.len() - 100).unwrap();
+        assert!(capped.len() <= full.len() - 100);
+        let parsed: serde_json::Value = serde_json::from_str(&capped).unwrap();
+        assert!(parsed["context"].get("payload").is_none());
+        assert_eq!(parsed["context"]["file"], "report.pdf");
+        assert_eq!(parsed["context"]["_truncated"], true);
+        
+        // A user field named `truncated` is neither overwritten nor evicted
+        entry.add_context("truncated", "no").unwrap();
+        let full = entry.to_json().unwrap();
+        let capped = entry.to_json_capped(full.len() - 100).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&capped).unwrap();
+        assert_eq!(parsed["context"]["truncated"], "no");
+        assert_eq!(parsed["context"]["_truncated"], true);
+        
+        // Impossible: even the bare entry is too large
+        assert!(entry.to_json_capped(10).is_err());
+    }
+    
+    #[test]
+    fn test_context_provenance() {
+        let mut entry = LogEntry::new("Request handled", LogLevel::Info);
+        entry.add_context("plain", 1).unwrap();
+        let json = entry.to_value().unwrap();
+        assert!(json.get("_provenance").is_none());
+        
+        entry.add_context_from("auth_middleware", "user_id", "12345").unwrap();
+        entry.add_context_from("router", "route", "/users").unwrap();
+        
+        assert_eq!(entry.provenance["user_id"], "auth_middleware");
+        assert_eq!(entry.provenance["route"], "router");
+        assert!(!entry.provenance.contains_key("plain"));
+        
+        let json = entry.to_value().unwrap();
+        assert_eq!(json["_provenance"]["user_id"], "auth_middleware");
+        assert_eq!(json["context"]["route"], "/users");
+    }
+    
+    #[test]
+    fn test_display() {
+        let mut entry = LogEntry::new("Cache miss", LogLevel::Warn);
+        entry.add_context("key", "user:42").unwrap();
+        entry.add_context("attempts", 3).unwrap();
+        
+        let line = entry.to_string();
+        assert!(line.starts_with("[WARN] "));
+        assert!(line.contains(&format_timestamp(&entry.metadata.timestamp)));
+        assert!(line.contains("Cache miss"));
+        assert!(line.ends_with("{attempts=3, key=user:42}"));
+        
+        let plain = LogEntry::new("No context", LogLevel::Info).to_string();
+        assert!(plain.ends_with("No context"));
+    }
+    
+    #[test]
+    fn test_with_capacity() {
+        let entry = LogEntry::with_capacity("Preallocated", LogLevel::Info, 16);
+        assert!(entry.context.capacity() >= 16);
+        assert!(entry.context.is_empty());
+        
+        let metadata = MetaData::with_capacity(8);
+        assert!(metadata.custom.capacity() >= 8);
+    }
+    
+    #[test]
+    fn test_here_captures_call_site() {
+        let expected_line = line!() + 1;
+        let entry = LogEntry::here("Captured", LogLevel::Info);
+        
+        assert_eq!(entry.metadata.line, Some(expected_line));
+        assert_eq!(entry.metadata.source.as_deref(), Some(file!()));
+    }
+    
+    #[test]
+    fn test_estimated_json_size() {
+        let mut entry = LogEntry::new("Sized", LogLevel::Info);
+        entry.add_context("payload", "x".repeat(100)).unwrap();
+        assert_eq!(entry.estimated_json_size(), entry.to_json().unwrap().len());
+    }
+    
+    #[test]
+    fn test_lightweight_entries_omit_metadata() {
+        let mut entry = LogEntry::lightweight("Hot path", LogLevel::Debug);
+        entry.add_context("iteration", 7).unwrap();
+        assert!(entry.metadata.id.is_nil());
+        
+        let json = entry.to_value().unwrap();
+        assert!(json.get("metadata").is_none());
+        assert_eq!(json["message"], "Hot path");
+        assert_eq!(json["context"]["iteration"], 7);
+        
+        let parsed: LogEntry = serde_json::from_value(json).unwrap();
+        assert!(parsed.metadata.is_omitted());
+        
+        let stripped = LogEntry::new("Stripped", LogLevel::Info).without_metadata();
+        assert!(stripped.to_value().unwrap().get("metadata").is_none());
+        assert!(LogEntry::new("Full", LogLevel::Info).to_value().unwrap().get("metadata").is_some());
+    }
+    
+    #[test]
+    fn test_metadata_writes_include_omitted_metadata() {
+        let entry = LogEntry::lightweight("Hot path", LogLevel::Debug).with_source("main.rs", 12);
+        assert!(!entry.metadata.is_omitted());
+        let json = entry.to_value().unwrap();
+        assert_eq!(json["metadata"]["source"], "main.rs");
+        assert_eq!(json["metadata"]["line"], 12);
+        
+        let mut entry = LogEntry::lightweight("Hot path", LogLevel::Debug);
+        entry.metadata.add_field("shard", 3).unwrap();
+        assert_eq!(entry.to_value().unwrap()["metadata"]["shard"], 3);
+    }
+    
+    #[test]
+    fn test_unknown_level_deserializes_as_info() {
+        let mut value = LogEntry::new("Bridged", LogLevel::Warn).to_value().unwrap();
+        value["level"] = serde_json::json!("notice");
+        let entry: LogEntry = serde_json::from_value(value).unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        
+        let level: LogLevel = serde_json::from_str(r#""critical""#).unwrap();
+        assert_eq!(level, LogLevel::Critical);
+        
+        assert_eq!("WARN".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert!("notice".parse::<LogLevel>().is_err());
+    }
+    
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_round_trip() {
+        let mut entry = LogEntry::new("Deploy finished", LogLevel::Info);
+        entry.add_context("release", serde_json::json!({"version": "1.4.2", "regions": ["eu", "us"]})).unwrap();
+        
+        let yaml = entry.to_yaml().unwrap();
+        assert!(yaml.contains("message: Deploy finished"));
+        assert!(yaml.contains("\n  release:\n    regions:\n    - eu\n"));
+        assert!(yaml.contains("\n    version: 1.4.2\n"));
+        
+        let parsed: LogEntry = serde_yaml::from_str(&yaml).unwrap();
+        assert!(parsed.semantically_eq(&entry));
+        assert_eq!(parsed.metadata.id, entry.metadata.id);
+    }
+    
+    #[test]
+    fn test_merge_custom_metadata() {
+        let mut entry = LogEntry::new("Enriched", LogLevel::Info);
+        entry.metadata.add_field("deployment", serde_json::json!({"region": "eu", "zone": "a"})).unwrap();
+        entry.metadata.add_field("team", "core").unwrap();
+        
+        let mut template = HashMap::new();
+        template.insert("deployment".to_string(), serde_json::json!({"zone": "b", "cluster": 3}));
+        template.insert("build".to_string(), serde_json::json!("abc123"));
+        entry.merge_metadata_custom(&template);
+        
+        let custom = &entry.metadata.custom;
+        assert_eq!(custom["deployment"], serde_json::json!({"region": "eu", "zone": "b", "cluster": 3}));
+        assert_eq!(custom["team"], "core");
+        assert_eq!(custom["build"], "abc123");
+    }
+    
+    #[test]
+    fn test_context_bytes_round_trip() {
+        let digest: Vec<u8> = (0u8..=255).collect();
+        let mut entry = LogEntry::new("Thumbnail attached", LogLevel::Info);
+        entry.add_context_bytes("digest", &digest).unwrap();
+        
+        assert!(entry.context["digest"]["_b64"].is_string());
+        assert_eq!(entry.context_bytes("digest"), Some(digest));
+        
+        entry.add_context("plain", "text").unwrap();
+        assert_eq!(entry.context_bytes("plain"), None);
+        assert_eq!(entry.context_bytes("missing"), None);
+    }
+    
+    #[test]
+    fn test_context_preview() {
+        let mut entry = LogEntry::new("Order placed", LogLevel::Info);
+        entry.add_context("status", "paid").unwrap();
+        entry.add_context("amount", 42.5).unwrap();
+        entry.add_context("items", vec!["book", "pen"]).unwrap();
+        entry.add_context("express", true).unwrap();
+        entry.add_context("customer", serde_json::json!({"id": 1})).unwrap();
+        
+        let preview = entry.context_preview(2);
+        assert_eq!(
+            preview,
+            vec![
+                ("amount".to_string(), "42.5".to_string()),
+                ("express".to_string(), "true".to_string()),
+            ]
+        );
+        
+        let all = entry.context_preview(10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2], ("status".to_string(), "paid".to_string()));
+        assert_eq!(entry.context_preview(10), all);
+    }
+    
+    #[test]
+    fn test_flag_future_timestamp() {
+        let tolerance = std::time::Duration::from_secs(60);
+        
+        let mut skewed = LogEntry::new("From the future", LogLevel::Info);
+        skewed.metadata.timestamp = Utc::now() + chrono::Duration::hours(2);
+        assert!(skewed.flag_future_timestamp(tolerance));
+        assert_eq!(skewed.context["clock_skew_detected"], true);
+        
+        let mut normal = LogEntry::new("Right on time", LogLevel::Info);
+        assert!(!normal.flag_future_timestamp(tolerance));
+        assert!(!normal.context.contains_key("clock_skew_detected"));
+    }
+    
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_with_structured_backtrace() {
+        let entry = LogEntry::new("Unexpected state", LogLevel::Error).with_structured_backtrace();
+        let frames = entry.context["backtrace"].as_array().unwrap();
+        assert!(!frames.is_empty());
+        assert!(frames.iter().all(|frame| frame.is_object()));
+    }
+    
+    #[test]
+    fn test_context_scope_restores_on_drop() {
+        let mut entry = LogEntry::new("Handling request", LogLevel::Info);
+        entry.add_context("request_id", "abc").unwrap();
+        entry.add_context("user", "alice").unwrap();
+        
+        {
+            let mut scope = entry.context_scope();
+            scope.add_context("step", "auth").unwrap();
+            scope.add_context("step", "db").unwrap();
+            scope.add_context("user", "bob").unwrap();
+            scope.remove_context("request_id");
+            
+            assert_eq!(scope.context["step"], "db");
+            assert_eq!(scope.context["user"], "bob");
+            assert!(!scope.context.contains_key("request_id"));
+        }
+        
+        assert!(!entry.context.contains_key("step"));
+        assert_eq!(entry.context["user"], "alice");
+        assert_eq!(entry.context["request_id"], "abc");
+    }
+    
+    #[test]
+    fn test_to_json_delta() {
+        let mut template = LogEntry::new("Health check", LogLevel::Info);
+        template.add_context("service", "api").unwrap();
+        template.add_context("status", "ok").unwrap();
+        template.add_context("latency_ms", 12).unwrap();
+        
+        let mut entry = template.clone();
+        entry.add_context("latency_ms", 15).unwrap();
+        
+        let delta = entry.to_json_delta(&template).unwrap();
+        assert_eq!(delta, r#"{"context":{"latency_ms":15}}"#);
+        assert!(delta.len() < entry.to_json().unwrap().len() / 4);
+        
+        let rebuilt = LogEntry::from_json_delta(&template, &delta).unwrap();
+        assert_eq!(rebuilt.to_value().unwrap(), entry.to_value().unwrap());
+        assert_eq!(template.to_json_delta(&template).unwrap(), "{}");
+    }
+    
+    #[test]
+    fn test_json_delta_removed_field_round_trip() {
+        let mut template = LogEntry::new("Health check", LogLevel::Info);
+        template.add_context("service", "api").unwrap();
+        template.add_context("status", "ok").unwrap();
+        
+        let mut entry = template.clone();
+        entry.context.remove("status");
+        entry.add_context("note", serde_json::Value::Null).unwrap();
+        
+        let delta = entry.to_json_delta(&template).unwrap();
+        let rebuilt = LogEntry::from_json_delta(&template, &delta).unwrap();
+        assert!(!rebuilt.context.contains_key("status"));
+        assert!(rebuilt.context["note"].is_null());
+        assert!(rebuilt.semantically_eq(&entry));
+        assert_eq!(rebuilt.metadata.id, entry.metadata.id);
+    }
+    
+    #[test]
+    fn test_partition_is_stable() {
+        let entry_for = |tenant: &str| {
+            let mut entry = LogEntry::new("Tenant event", LogLevel::Info);
+            entry.add_context("tenant_id", tenant).unwrap();
+            entry
+        };
+        
+        for tenant in ["acme", "globex", "initech"] {
+            let shard = entry_for(tenant).partition("tenant_id", 8);
+            assert!(shard < 8);
+            for _ in 0..10 {
+                assert_eq!(entry_for(tenant).partition("tenant_id", 8), shard);
+            }
+        }
+        
+        let untagged = LogEntry::new("No tenant", LogLevel::Info);
+        assert_eq!(untagged.partition("tenant_id", 8), 0);
+        assert_eq!(entry_for("acme").partition("tenant_id", 0), 0);
+    }
+    
+    #[test]
+    fn test_with_sequence() {
+        let first = LogEntry::new("first", LogLevel::Info).with_sequence();
+        let second = LogEntry::new("second", LogLevel::Info).with_sequence();
+        
+        let (a, b) = (first.metadata.sequence.unwrap(), second.metadata.sequence.unwrap());
+        assert!(b > a);
+        assert!(first.to_json().unwrap().contains(&format!(r#""sequence":{}"#, a)));
+        assert!(!LogEntry::new("plain", LogLevel::Info).to_json().unwrap().contains("sequence"));
+    }
+    
+    #[test]
+    fn test_from_json_safe() {
+        let json = LogEntry::new("Parsed safely", LogLevel::Warn).to_json().unwrap();
+        let entry = LogEntry::from_json_safe(&json, 8, 4096).unwrap();
+        assert_eq!(entry.message, "Parsed safely");
+        
+        let oversized = LogEntry::from_json_safe(&json, 8, json.len() - 1);
+        assert!(matches!(oversized, Err(Error::LoggingError(msg)) if msg.contains("byte limit")));
+        
+        let nested = format!(
+            r#"{{"message":"deep","level":"info","context":{{"a":{}1{}}}}}"#,
+            "[".repeat(10_000),
+            "]".repeat(10_000)
+        );
+        let deep = LogEntry::from_json_safe(&nested, 32, usize::MAX);
+        assert!(matches!(deep, Err(Error::LoggingError(msg)) if msg.contains("nesting depth")));
+        
+        assert!(LogEntry::from_json_safe("{\"message\":", 8, 4096).is_err());
+    }
+    
+    #[test]
+    fn test_to_flat_pairs() {
+        let mut entry = LogEntry::new("Exported", LogLevel::Warn).with_thread("main");
+        entry.add_context("user", serde_json::json!({"id": 7, "name": "ada"})).unwrap();
+        entry.add_context("attempt", 2).unwrap();
+        
+        let pairs = entry.to_flat_pairs();
+        let keys: Vec<&str> = pairs.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "message", "level", "timestamp",
+                "context.attempt", "context.user.id", "context.user.name",
+                "metadata.id", "metadata.thread",
+            ]
+        );
+        assert_eq!(pairs[1].1, "warn");
+        assert_eq!(pairs[2].1, format_timestamp(&entry.metadata.timestamp));
+        assert_eq!(pairs[3].1, "2");
+        assert_eq!(pairs[5].1, "ada");
+    }
+    
+    #[test]
+    fn test_body_serialization() {
+        let entry = LogEntry::new("Deploy failed", LogLevel::Error);
+        assert!(!entry.to_json().unwrap().contains("body"));
+        
+        let entry = entry.with_body("Step 3 of 5 timed out after 30s\nRolled back to v1.4.2");
+        let json = entry.to_json().unwrap();
+        assert!(json.contains(r#""body":"Step 3 of 5 timed out after 30s\nRolled back to v1.4.2""#));
+        
+        let parsed: LogEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.body, entry.body);
+        assert_eq!(parsed.message, "Deploy failed");
+    }
+    
+    #[test]
+    fn test_add_namespaced_context() {
+        #[derive(Serialize)]
+        struct DbStats {
+            pool_size: u32,
+            host: &'static str,
+        }
+        
+        let mut entry = LogEntry::new("Query slow", LogLevel::Warn);
+        entry.add_context("pool_size", 1).unwrap();
+        entry.add_namespaced_context("db", &DbStats { pool_size: 16, host: "primary" }).unwrap();
+        
+        assert_eq!(entry.context["db.pool_size"], 16);
+        assert_eq!(entry.context["db.host"], "primary");
+        assert_eq!(entry.context["pool_size"], 1);
+        
+        let err = entry.add_namespaced_context("db", &42);
+        assert!(matches!(err, Err(Error::LoggingError(_))));
+    }
+    
+    #[test]
+    fn test_parse_with_suggestion() {
+        assert_eq!(LogLevel::parse_with_suggestion("WARN").unwrap(), LogLevel::Warn);
+        
+        match LogLevel::parse_with_suggestion("waring") {
+            Err(Error::LoggingError(msg)) => assert!(msg.contains("did you mean 'warn'?"), "{}", msg),
+            other => panic!("expected a suggestion, got {:?}", other),
+        }
+        match LogLevel::parse_with_suggestion("critcal") {
+            Err(Error::LoggingError(msg)) => assert!(msg.contains("'critical'"), "{}", msg),
+            other => panic!("expected a suggestion, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_as_span() {
+        let ok = LogEntry::new("Fetched profile", LogLevel::Info)
+            .as_span(std::time::Duration::from_millis(42), SpanStatus::Ok);
+        assert_eq!(ok.context["span.duration_ms"], 42);
+        assert_eq!(ok.context["span.status"], "ok");
+        assert_eq!(ok.level, LogLevel::Info);
+        
+        let failed = LogEntry::new("Fetched profile", LogLevel::Info)
+            .as_span(std::time::Duration::from_secs(3), SpanStatus::Error);
+        assert_eq!(failed.context["span.duration_ms"], 3000);
+        assert_eq!(failed.context["span.status"], "error");
+        assert_eq!(failed.level, LogLevel::Warn);
+        
+        let fatal = LogEntry::new("Crashed", LogLevel::Fatal)
+            .as_span(std::time::Duration::ZERO, SpanStatus::Error);
+        assert_eq!(fatal.level, LogLevel::Fatal);
+    }
+    
+    #[test]
+    fn test_max_context_fields() {
+        let mut entry = LogEntry::new("Bounded", LogLevel::Info).with_max_context_fields(2);
+        entry.add_context("a", 1).unwrap();
+        entry.add_context("b", 2).unwrap();
+        
+        let err = entry.add_context("c", 3);
+        assert!(matches!(err, Err(Error::LoggingError(msg)) if msg.contains("limit of 2")));
+        assert_eq!(entry.context.len(), 2);
+        
+        // Replacing an existing field doesn't grow the context
+        entry.add_context("a", 10).unwrap();
+        assert_eq!(entry.context["a"], 10);
+        
+        assert!(entry.context_scope().add_context("d", 4).is_err());
+        
+        // Every context method honours the cap
+        assert!(entry.add_context_bytes("blob", b"raw").is_err());
+        assert!(entry.add_context_custom("e", 5, &ValueFormatterRegistry::new()).is_err());
+        assert!(entry.extend_context(serde_json::json!({"a": 11, "f": 6})).is_err());
+        assert!(entry.add_namespaced_context("http", &serde_json::json!({"status": 200})).is_err());
+        assert_eq!(entry.context.len(), 2);
+        assert_eq!(entry.context["a"], 10);
+        entry.extend_context(serde_json::json!({"b": 20})).unwrap();
+        
+        // The cap survives redaction and aggregation
+        Redactor::new(Vec::<String>::new()).redact_paths(&mut entry, &["/context/a"]).unwrap();
+        assert_eq!(entry.max_context_fields(), Some(2));
+        assert!(entry.add_context("c", 3).is_err());
+        
+        let summary = LogEntry::aggregate(&[entry.clone(), entry]).unwrap();
+        assert_eq!(summary.max_context_fields(), Some(2));
+    }
+    
+    #[test]
+    fn test_with_uptime() {
+        let first = LogEntry::new("boot", LogLevel::Info).with_uptime();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let second = LogEntry::new("ready", LogLevel::Info).with_uptime();
+        
+        let (a, b) = (first.metadata.uptime_ns.unwrap(), second.metadata.uptime_ns.unwrap());
+        assert!(b > a);
+        assert!(second.to_json().unwrap().contains(&format!(r#""uptime_ns":{}"#, b)));
+    }
+    
+    #[test]
+    fn test_with_fields() {
+        let entry = LogEntry::with_fields("Order placed", LogLevel::Info, &[
+            ("order_id", serde_json::json!("A-1001")),
+            ("items", serde_json::json!(3)),
+            ("express", serde_json::json!(true)),
+        ]).unwrap();
+        
+        assert_eq!(entry.message, "Order placed");
+        assert_eq!(entry.context.len(), 3);
+        assert_eq!(entry.context["order_id"], "A-1001");
+        assert_eq!(entry.context["items"], 3);
+        assert_eq!(entry.context["express"], true);
+    }
+    
+    #[test]
+    fn test_ttl_expiry() {
+        let entry = LogEntry::new("Short-lived", LogLevel::Debug)
+            .with_ttl(std::time::Duration::from_secs(3600));
+        let created = entry.metadata.timestamp;
+        assert_eq!(entry.metadata.ttl_seconds, Some(3600));
+        
+        assert!(!entry.metadata.is_expired(created));
+        assert!(!entry.metadata.is_expired(created + chrono::Duration::seconds(3599)));
+        assert!(entry.metadata.is_expired(created + chrono::Duration::seconds(3600)));
+        assert!(entry.metadata.is_expired(created + chrono::Duration::days(2)));
+        
+        let forever = LogEntry::new("Kept", LogLevel::Info);
+        assert!(!forever.metadata.is_expired(created + chrono::Duration::days(10_000)));
+    }
+    
+    #[test]
+    fn test_setters() {
+        let mut entry = LogEntry::new("draft", LogLevel::Debug);
+        entry.set_level(LogLevel::Error).set_message("Payment declined");
+        
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Payment declined");
+    }
+    
+    #[test]
+    fn test_trace_context_flags() {
+        let sampled = LogEntry::new("Handled", LogLevel::Info)
+            .with_trace_context("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .unwrap();
+        assert_eq!(sampled.context["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(sampled.context["span_id"], "00f067aa0ba902b7");
+        assert_eq!(sampled.metadata.trace_flags, Some(1));
+        assert!(sampled.metadata.is_sampled());
+        
+        let unsampled = LogEntry::new("Handled", LogLevel::Info)
+            .with_trace_context("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00")
+            .unwrap();
+        assert!(!unsampled.metadata.is_sampled());
+        assert!(!LogEntry::new("No trace", LogLevel::Info).metadata.is_sampled());
+        
+        for bad in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(LogEntry::new("x", LogLevel::Info).with_trace_context(bad).is_err(), "{}", bad);
+        }
+    }
+    
+    #[test]
+    fn test_add_context_sanitized() {
+        let mut entry = LogEntry::new("Profile updated", LogLevel::Info);
+        entry.add_context_sanitized("user.name", "ada").unwrap();
+        entry.add_context("raw.key", 1).unwrap();
+        
+        assert_eq!(entry.context["user_name"], "ada");
+        assert!(!entry.context.contains_key("user.name"));
+        assert!(entry.context.contains_key("raw.key"));
+        
+        let value = serde_json::to_value(&entry.context).unwrap();
+        assert_eq!(crate::util::get_nested_value(&value, "user_name"), Some(&serde_json::json!("ada")));
+    }
+    
+    #[test]
+    fn test_with_experiments() {
+        let experiments = HashMap::from([
+            ("new_checkout".to_string(), "variant_b".to_string()),
+            ("dark_mode".to_string(), "control".to_string()),
+        ]);
+        let entry = LogEntry::new("Checkout rendered", LogLevel::Info).with_experiments(&experiments);
+        
+        assert_eq!(entry.context.len(), 1);
+        assert_eq!(entry.context["experiments"]["new_checkout"], "variant_b");
+        assert_eq!(entry.context["experiments"]["dark_mode"], "control");
+        assert!(entry.has_experiment("dark_mode"));
+        assert!(!entry.has_experiment("search_v2"));
+    }
+    
+    #[test]
+    fn test_with_metrics() {
+        let metrics = HashMap::from([
+            ("queue_depth".to_string(), 42.0),
+            ("cpu_load".to_string(), 0.75),
+            ("hit_ratio".to_string(), f64::NAN),
+            ("rate".to_string(), f64::INFINITY),
+        ]);
+        let entry = LogEntry::new("Health check", LogLevel::Info).with_metrics(&metrics);
+        
+        assert_eq!(entry.context["metrics"]["queue_depth"], 42.0);
+        assert_eq!(entry.context["metrics"]["cpu_load"], 0.75);
+        assert!(entry.context["metrics"]["hit_ratio"].is_null());
+        assert!(entry.context["metrics"]["rate"].is_null());
+        
+        let json = entry.to_json().unwrap();
+        assert!(json.contains("\"hit_ratio\":null"));
+    }
+    
+    #[test]
+    fn test_with_elapsed() {
+        let elapsed = std::time::Duration::from_millis(65_250);
+        
+        let millis = LogEntry::new("Export finished", LogLevel::Info)
+            .with_elapsed(elapsed, DurationFormat::Millis);
+        assert_eq!(millis.context["elapsed_ms"], 65_250);
+        
+        let iso = LogEntry::new("Export finished", LogLevel::Info)
+            .with_elapsed(elapsed, DurationFormat::Iso8601);
+        assert_eq!(iso.context["elapsed"], "PT1M5.25S");
+    }
+    
+    #[test]
+    fn test_dedup_context_arrays() {
+        let mut entry = LogEntry::new("Tagged", LogLevel::Info);
+        entry.add_context("tags", vec!["a", "a", "b"]).unwrap();
+        entry.add_context("mixed", serde_json::json!([1, {"k": 1}, 1, {"k": 1}, true, true])).unwrap();
+        entry.add_context("name", "a").unwrap();
+        
+        assert_eq!(entry.dedup_context_arrays(), 3);
+        assert_eq!(entry.context["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(entry.context["mixed"], serde_json::json!([1, {"k": 1}, {"k": 1}, true]));
+        assert_eq!(entry.context["name"], "a");
+    }
+    
+    #[test]
+    fn test_level_glyphs() {
+        for level in LogLevel::ALL {
+            assert!(!level.glyph().is_empty());
+        }
+        assert_eq!(LogLevel::Error.glyph(), "\u{2716}");
+        assert_eq!(LogLevel::Info.glyph(), "\u{2139}");
+    }
+    
+    #[test]
+    fn test_non_finite_context() {
+        let mut default = LogEntry::new("Ratio", LogLevel::Info);
+        default.add_context("ratio", f64::NAN).unwrap();
+        assert!(default.context["ratio"].is_null());
+        
+        let mut sentinel = LogEntry::new("Ratio", LogLevel::Info)
+            .with_non_finite(NonFiniteMode::Sentinel);
+        sentinel.add_context("ratio", f64::NAN).unwrap();
+        sentinel.add_context("samples", vec![0.5, f64::INFINITY]).unwrap();
+        assert_eq!(sentinel.context["ratio"], "NaN");
+        assert_eq!(sentinel.context["samples"], serde_json::json!([0.5, "Infinity"]));
+        
+        let mut strict = LogEntry::new("Ratio", LogLevel::Info)
+            .with_non_finite(NonFiniteMode::Reject);
+        assert!(strict.add_context("ratio", f64::NEG_INFINITY).is_err());
+        assert!(strict.context.is_empty());
+        
+        // The mode survives redaction
+        strict.add_context("token", "abc").unwrap();
+        Redactor::new(Vec::<String>::new()).redact_paths(&mut strict, &["/context/token"]).unwrap();
+        assert_eq!(strict.non_finite(), NonFiniteMode::Reject);
+        assert!(strict.add_context("ratio", f64::NAN).is_err());
+        
+        // Every path that serializes a value honours the mode
+        let registry = ValueFormatterRegistry::new();
+        assert!(strict.add_context_custom("ratio", f64::NAN, &registry).is_err());
+        assert!(strict.add_namespaced_context("stats", &serde_json::json!({"ok": 1})).is_ok());
+        assert!(strict.add_namespaced_context("stats", &HashMap::from([("mean", f64::NAN)])).is_err());
+        assert!(strict.add_context_from("probe", "ratio", f64::INFINITY).is_err());
+        assert!(!strict.context.contains_key("ratio"));
+        
+        sentinel.add_context_custom("custom", f64::INFINITY, &registry).unwrap();
+        sentinel.add_namespaced_context("stats", &HashMap::from([("mean", f64::NAN)])).unwrap();
+        assert_eq!(sentinel.context["custom"], "Infinity");
+        assert_eq!(sentinel.context["stats.mean"], "NaN");
+    }
+    
+    #[test]
+    fn test_aggregate() {
+        let base = Utc::now();
+        let entries: Vec<LogEntry> = [(2, LogLevel::Warn, 3), (0, LogLevel::Info, 1), (5, LogLevel::Info, 2)]
+            .into_iter()
+            .map(|(offset, level, attempt)| {
+                let mut entry = LogEntry::new("Retrying upload", level);
+                entry.metadata.timestamp = base + chrono::Duration::seconds(offset);
+                entry.add_context("request", serde_json::json!({"attempt": attempt})).unwrap();
+                entry.add_context(format!("host_{}", attempt), "cdn").unwrap();
+                entry
+            })
+            .collect();
+        
+        let summary = LogEntry::aggregate(&entries).unwrap();
+        assert_eq!(summary.message, "Retrying upload");
+        assert_eq!(summary.level, LogLevel::Warn);
+        assert_eq!(summary.metadata.custom["count"], 3);
+        assert_eq!(summary.metadata.timestamp, base);
+        assert_eq!(summary.metadata.custom["first_timestamp"], format_timestamp(&base));
+        assert_eq!(
+            summary.metadata.custom["last_timestamp"],
+            format_timestamp(&(base + chrono::Duration::seconds(5)))
+        );
+        assert_eq!(summary.context["request"]["attempt"], 2);
+        assert_eq!(summary.context.len(), 4);
+        
+        let mixed = vec![entries[0].clone(), LogEntry::new("Upload failed", LogLevel::Error)];
+        assert!(LogEntry::aggregate(&mixed).is_err());
+        let summary = LogEntry::aggregate_as(&mixed, "Upload trouble").unwrap();
+        assert_eq!(summary.metadata.custom["count"], 2);
+        assert_eq!(summary.level, LogLevel::Error);
+        assert!(LogEntry::aggregate(&[]).is_err());
+    }
+    
+    #[test]
+    fn test_to_writer() {
+        let mut entry = LogEntry::new("Written directly", LogLevel::Info);
+        entry.add_context("path", "/var/log/app.json").unwrap();
+        
+        let mut compact = Vec::new();
+        entry.to_writer(&mut compact).unwrap();
+        assert_eq!(compact, entry.to_json().unwrap().into_bytes());
+        
+        let mut pretty = Vec::new();
+        entry.to_writer_pretty(&mut pretty).unwrap();
+        assert_eq!(pretty, entry.to_pretty_json().unwrap().into_bytes());
+        
+        let mut full = [0u8; 8];
+        let err = entry.to_writer(&mut full[..]).unwrap_err();
+        assert!(matches!(err, Error::IoError(_)));
+    }
+    
+    #[test]
+    fn test_stamp_received() {
+        let mut entry = LogEntry::new("Shipped from edge", LogLevel::Info);
+        entry.metadata.timestamp = Utc::now() - chrono::Duration::minutes(5);
+        assert!(entry.metadata.received_at.is_none());
+        assert!(entry.to_value().unwrap()["metadata"].get("received_at").is_none());
+        
+        entry.metadata.stamp_received();
+        let received = entry.metadata.received_at.unwrap();
+        assert!(received > entry.metadata.timestamp);
+        
+        let restored: LogEntry = serde_json::from_str(&entry.to_json().unwrap()).unwrap();
+        assert_eq!(restored.metadata.received_at, Some(received));
+    }
+    
+    #[test]
+    fn test_compact_embedded_json() {
+        let body = "{\n  \"user\": \"ada\",\n  \"items\": [\n    1,\n    2\n  ]\n}";
+        let mut entry = LogEntry::new("Request captured", LogLevel::Debug);
+        entry.add_context("request_body", body).unwrap();
+        entry.add_context("note", "{ not json").unwrap();
+        entry.add_context("retries", "42").unwrap();
+        
+        assert_eq!(entry.compact_embedded_json(EmbeddedJson::Minify), 1);
+        assert_eq!(entry.context["request_body"], r#"{"items":[1,2],"user":"ada"}"#);
+        assert_eq!(entry.context["note"], "{ not json");
+        assert_eq!(entry.context["retries"], "42");
+        assert_eq!(entry.compact_embedded_json(EmbeddedJson::Minify), 0);
+        
+        assert_eq!(entry.compact_embedded_json(EmbeddedJson::Parse), 1);
+        assert_eq!(entry.context["request_body"]["items"], serde_json::json!([1, 2]));
+        assert_eq!(entry.context["note"], "{ not json");
+    }
+    
+    #[test]
+    fn test_level_from_http_status() {
+        assert_eq!(LogLevel::from_http_status(101), LogLevel::Info);
+        assert_eq!(LogLevel::from_http_status(200), LogLevel::Info);
+        assert_eq!(LogLevel::from_http_status(304), LogLevel::Info);
+        assert_eq!(LogLevel::from_http_status(399), LogLevel::Info);
+        assert_eq!(LogLevel::from_http_status(400), LogLevel::Warn);
+        assert_eq!(LogLevel::from_http_status(404), LogLevel::Warn);
+        assert_eq!(LogLevel::from_http_status(499), LogLevel::Warn);
+        assert_eq!(LogLevel::from_http_status(500), LogLevel::Error);
+        assert_eq!(LogLevel::from_http_status(503), LogLevel::Error);
+    }
+    
+    #[test]
+    fn test_extend_context() {
+        let mut entry = LogEntry::new("Request handled", LogLevel::Info);
+        entry.add_context("b", serde_json::json!({"d": 3})).unwrap();
+        entry.extend_context(serde_json::json!({"a": 1, "b": {"c": 2}})).unwrap();
+        
+        assert_eq!(entry.context["a"], serde_json::json!(1));
+        assert_eq!(entry.context["b"], serde_json::json!({"c": 2, "d": 3}));
+        
+        assert!(entry.extend_context(serde_json::json!([1, 2])).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Level-specific enrichment of log entries
+
+use std::collections::HashMap;
+
+use crate::core::{LogEntry, LogLevel};
+
+/// Trait for adding information to entries of a particular level
+pub trait LevelEnricher: Send + Sync {
+    /// Enrich an entry logged at `level`
+    fn enrich(&self, level: LogLevel, entry: &mut LogEntry);
+}
+
+impl<F> LevelEnricher for F
+where
+    F: Fn(LogLevel, &mut LogEntry) + Send + Sync,
+{
+    fn enrich(&self, level: LogLevel, entry: &mut LogEntry) {
+        self(level, entry)
+    }
+}
+
+/// Registry mapping log levels to their enrichers
+///
+/// Enrichers registered for a level run in registration order on entries
+/// of exactly that level.
+#[derive(Default)]
+pub struct EnricherRegistry {
+    enrichers: HashMap<LogLevel, Vec<Box<dyn LevelEnricher>>>,
+}
+
+impl EnricherRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Register an enricher for a level
+    pub fn register<E: LevelEnricher + 'static>(&mut self, level: LogLevel, enricher: E) -> &mut Self {
+        self.enrichers.entry(level).or_default().push(Box::new(enricher));
+        self
+    }
+    
+    /// Run the enrichers registered for the entry's level
+    pub fn apply(&self, entry: &mut LogEntry) {
+        let level = entry.level;
+        if let Some(enrichers) = self.enrichers.get(&level) {
+            for enricher in enrichers {
+                enricher.enrich(level, entry);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for EnricherRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts: HashMap<&LogLevel, usize> = self.enrichers
+            .iter()
+            .map(|(level, enrichers)| (level, enrichers.len()))
+            .collect();
+        f.debug_struct("EnricherRegistry").field("enrichers", &counts).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    struct RunbookEnricher {
+        url: &'static str,
+    }
+    
+    impl LevelEnricher for RunbookEnricher {
+        fn enrich(&self, _level: LogLevel, entry: &mut LogEntry) {
+            let _ = entry.add_context("runbook", self.url);
+        }
+    }
+    
+    #[test]
+    fn test_enricher_applies_to_its_level_only() {
+        let mut registry = EnricherRegistry::new();
+        registry.register(LogLevel::Error, RunbookEnricher { url: "https://runbooks.example/db" });
+        registry.register(LogLevel::Trace, |_level: LogLevel, entry: &mut LogEntry| {
+            let _ = entry.add_context("sampled", true);
+        });
+        
+        let mut error = LogEntry::new("Database unreachable", LogLevel::Error);
+        registry.apply(&mut error);
+        assert_eq!(error.context["runbook"], "https://runbooks.example/db");
+        
+        let mut info = LogEntry::new("Database reachable", LogLevel::Info);
+        registry.apply(&mut info);
+        assert!(info.context.is_empty());
+        
+        let mut trace = LogEntry::new("Query plan", LogLevel::Trace);
+        registry.apply(&mut trace);
+        assert_eq!(trace.context["sampled"], true);
+        assert!(!trace.context.contains_key("runbook"));
+    }
+}
@@ -11,6 +11,19 @@ pub enum Error {
     #[error("Extension error: {0}")]
     ExtensionError(String),
     
+    /// Error when reading or writing log data
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    /// Error when parsing a single line of line-delimited input
+    #[error("Parse error on line {line}: {source}")]
+    ParseError {
+        /// Line number (1-based) of the malformed input
+        line: usize,
+        /// Underlying JSON error
+        source: serde_json::Error,
+    },
+    
     /// Error with log formatting
     #[error("Formatter error: {0}")]
     FormatterError(String),
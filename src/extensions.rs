@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::any::{Any, TypeId};
+use crate::core::LogEntry;
 use crate::error::{Result, Error};
 
 /// Trait for extensions to ChrysalisRS
@@ -24,12 +25,31 @@ pub trait Extension: Send + Sync {
     
     /// Get mutable extension as Any for downcasting
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    
+    /// Get a deprecation notice if the extension is deprecated
+    ///
+    /// Deprecated extensions still initialize normally; the notice is
+    /// surfaced once by `ExtensionRegistry::initialize_all`.
+    fn deprecation(&self) -> Option<&str> {
+        None
+    }
+    
+    /// Process a log entry before it is emitted
+    ///
+    /// The default implementation leaves the entry unchanged.
+    fn process(&self, _entry: &mut LogEntry) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Registry for managing extensions
+///
+/// Extensions are kept in registration order, which is the order
+/// `initialize_all`, `process_all` and `shutdown_all` visit them in.
 pub struct ExtensionRegistry {
-    extensions: HashMap<String, Box<dyn Extension>>,
+    extensions: Vec<(String, Box<dyn Extension>)>,
     type_map: HashMap<TypeId, String>,
+    initialized: bool,
 }
 
 impl Default for ExtensionRegistry {
@@ -42,35 +62,46 @@ impl ExtensionRegistry {
     /// Create a new extension registry
     pub fn new() -> Self {
         Self {
-            extensions: HashMap::new(),
+            extensions: Vec::new(),
             type_map: HashMap::new(),
+            initialized: false,
         }
     }
     
+    /// Index of the extension registered under `name`
+    fn position(&self, name: &str) -> Option<usize> {
+        self.extensions.iter().position(|(registered, _)| registered == name)
+    }
+    
+    /// Storage slot of the extension registered under `name`
+    fn slot_mut(&mut self, name: &str) -> Option<&mut Box<dyn Extension>> {
+        self.position(name).map(move |index| &mut self.extensions[index].1)
+    }
+    
     /// Register an extension
     pub fn register<E: Extension + 'static>(&mut self, extension: E) -> Result<()> {
         let name = extension.name().to_string();
         let type_id = TypeId::of::<E>();
         
-        if self.extensions.contains_key(&name) {
+        if self.position(&name).is_some() {
             return Err(Error::ExtensionError(format!(
                 "Extension with name '{}' is already registered", name
             )));
         }
         
         self.type_map.insert(type_id, name.clone());
-        self.extensions.insert(name, Box::new(extension));
+        self.extensions.push((name, Box::new(extension)));
         Ok(())
     }
     
     /// Get an extension by name
     pub fn get(&self, name: &str) -> Option<&dyn Extension> {
-        self.extensions.get(name).map(|ext| ext.as_ref())
+        self.position(name).map(|index| self.extensions[index].1.as_ref())
     }
     
     /// Get a mutable extension by name
     pub fn get_mut(&mut self, name: &str) -> Option<&mut dyn Extension> {
-        if let Some(ext) = self.extensions.get_mut(name) {
+        if let Some(ext) = self.slot_mut(name) {
             Some(&mut **ext)
         } else {
             None
@@ -82,7 +113,7 @@ impl ExtensionRegistry {
         let type_id = TypeId::of::<E>();
         
         if let Some(name) = self.type_map.get(&type_id) {
-            if let Some(ext) = self.extensions.get(name) {
+            if let Some(ext) = self.get(name) {
                 return ext.as_any().downcast_ref::<E>();
             }
         }
@@ -95,7 +126,7 @@ impl ExtensionRegistry {
         let type_id = TypeId::of::<E>();
         
         if let Some(name) = self.type_map.get(&type_id).cloned() {
-            if let Some(ext) = self.extensions.get_mut(&name) {
+            if let Some(ext) = self.slot_mut(&name) {
                 return ext.as_any_mut().downcast_mut::<E>();
             }
         }
@@ -103,19 +134,82 @@ impl ExtensionRegistry {
         None
     }
     
+    /// Replace the extension registered under the same name
+    ///
+    /// The swap happens in place, keeping the extension's position, and the
+    /// type map is updated to point at the new extension. If the registry has
+    /// been initialized, the new extension is initialized before the swap and
+    /// the old one is shut down after it. Returns the previous extension, or
+    /// `None` if no extension with that name was registered (the new one is
+    /// registered).
+    pub fn replace<E: Extension + 'static>(&mut self, mut extension: E) -> Result<Option<Box<dyn Extension>>> {
+        let name = extension.name().to_string();
+        
+        if self.initialized {
+            extension.initialize().map_err(|e| Error::ExtensionError(format!(
+                "Failed to initialize extension '{}': {}", name, e
+            )))?;
+        }
+        
+        let Some(slot) = self.slot_mut(&name) else {
+            self.register(extension)?;
+            return Ok(None);
+        };
+        
+        let mut old = std::mem::replace(slot, Box::new(extension));
+        self.type_map.retain(|_, registered| *registered != name);
+        self.type_map.insert(TypeId::of::<E>(), name.clone());
+        
+        if self.initialized {
+            old.shutdown().map_err(|e| Error::ExtensionError(format!(
+                "Failed to shutdown extension '{}': {}", name, e
+            )))?;
+        }
+        Ok(Some(old))
+    }
+    
     /// Remove an extension by name
     pub fn remove(&mut self, name: &str) -> Option<Box<dyn Extension>> {
-        self.extensions.remove(name)
+        let index = self.position(name)?;
+        self.type_map.retain(|_, registered| registered != name);
+        Some(self.extensions.remove(index).1)
     }
     
     /// Initialize all extensions
-    pub fn initialize_all(&mut self) -> Result<()> {
+    ///
+    /// Returns the deprecation warnings of any deprecated extensions that
+    /// were initialized.
+    pub fn initialize_all(&mut self) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        
         for (name, ext) in &mut self.extensions {
             if let Err(e) = ext.initialize() {
                 return Err(Error::ExtensionError(format!(
                     "Failed to initialize extension '{}': {}", name, e
                 )));
             }
+            
+            if let Some(notice) = ext.deprecation() {
+                warnings.push(format!("Extension '{}' is deprecated: {}", name, notice));
+            }
+        }
+        self.initialized = true;
+        Ok(warnings)
+    }
+    
+    /// Run the `process` hook of every enabled extension on an entry
+    ///
+    /// Extensions run in registration order; stops at the first error.
+    pub fn process_all(&self, entry: &mut LogEntry) -> Result<()> {
+        for (name, ext) in &self.extensions {
+            if !ext.is_enabled() {
+                continue;
+            }
+            if let Err(e) = ext.process(entry) {
+                return Err(Error::ExtensionError(format!(
+                    "Extension '{}' failed to process entry: {}", name, e
+                )));
+            }
         }
         Ok(())
     }
@@ -129,6 +223,152 @@ impl ExtensionRegistry {
                 )));
             }
         }
+        self.initialized = false;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    struct TestExtension {
+        name: &'static str,
+        enabled: bool,
+        deprecation: Option<&'static str>,
+        threshold: u32,
+        initialized: bool,
+        shut_down: bool,
+    }
+    
+    impl TestExtension {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                enabled: true,
+                deprecation: None,
+                threshold: 0,
+                initialized: false,
+                shut_down: false,
+            }
+        }
+    }
+    
+    impl Extension for TestExtension {
+        fn name(&self) -> &str {
+            self.name
+        }
+        
+        fn initialize(&mut self) -> Result<()> {
+            self.initialized = true;
+            Ok(())
+        }
+        
+        fn shutdown(&mut self) -> Result<()> {
+            self.shut_down = true;
+            Ok(())
+        }
+        
+        fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+        
+        fn set_enabled(&mut self, enabled: bool) {
+            self.enabled = enabled;
+        }
+        
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+        
+        fn deprecation(&self) -> Option<&str> {
+            self.deprecation
+        }
+        
+        fn process(&self, entry: &mut LogEntry) -> Result<()> {
+            let trail = entry.context
+                .entry("trail".to_string())
+                .or_insert_with(|| serde_json::json!([]));
+            if let Some(trail) = trail.as_array_mut() {
+                trail.push(format!("{}:{}", self.name, self.threshold).into());
+            }
+            Ok(())
+        }
+    }
+    
+    fn trail(registry: &ExtensionRegistry) -> serde_json::Value {
+        let mut entry = LogEntry::new("probe", crate::core::LogLevel::Info);
+        registry.process_all(&mut entry).unwrap();
+        entry.context["trail"].clone()
+    }
+    
+    #[test]
+    fn test_initialize_all_surfaces_deprecations() {
+        let mut registry = ExtensionRegistry::new();
+        let mut legacy = TestExtension::new("legacy");
+        legacy.deprecation = Some("use 'modern' instead");
+        registry.register(legacy).unwrap();
+        
+        let warnings = registry.initialize_all().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("legacy"));
+        assert!(warnings[0].contains("use 'modern' instead"));
+    }
+    
+    #[test]
+    fn test_replace_extension() {
+        let mut registry = ExtensionRegistry::new();
+        let mut original = TestExtension::new("limits");
+        original.threshold = 10;
+        registry.register(original).unwrap();
+        
+        let mut updated = TestExtension::new("limits");
+        updated.threshold = 20;
+        let old = registry.replace(updated).unwrap().unwrap();
+        
+        let old = old.as_any().downcast_ref::<TestExtension>().unwrap();
+        assert_eq!(old.threshold, 10);
+        assert_eq!(registry.get_by_type::<TestExtension>().unwrap().threshold, 20);
+        
+        // Replacing an unknown name registers it
+        assert!(registry.replace(TestExtension::new("fresh")).unwrap().is_none());
+        assert!(registry.get("fresh").is_some());
+    }
+    
+    #[test]
+    fn test_replace_keeps_registration_order() {
+        let mut registry = ExtensionRegistry::new();
+        registry.register(TestExtension::new("first")).unwrap();
+        registry.register(TestExtension::new("second")).unwrap();
+        registry.register(TestExtension::new("third")).unwrap();
+        assert_eq!(trail(&registry), serde_json::json!(["first:0", "second:0", "third:0"]));
+        
+        let mut updated = TestExtension::new("second");
+        updated.threshold = 5;
+        registry.replace(updated).unwrap();
+        assert_eq!(trail(&registry), serde_json::json!(["first:0", "second:5", "third:0"]));
+    }
+    
+    #[test]
+    fn test_replace_runs_lifecycle_hooks() {
+        // Before initialize_all, replace leaves both extensions untouched
+        let mut registry = ExtensionRegistry::new();
+        registry.register(TestExtension::new("limits")).unwrap();
+        let old = registry.replace(TestExtension::new("limits")).unwrap().unwrap();
+        assert!(!old.as_any().downcast_ref::<TestExtension>().unwrap().shut_down);
+        assert!(!registry.get_by_type::<TestExtension>().unwrap().initialized);
+        
+        // Once initialized, the new extension is initialized and the old one shut down
+        registry.initialize_all().unwrap();
+        let old = registry.replace(TestExtension::new("limits")).unwrap().unwrap();
+        assert!(old.as_any().downcast_ref::<TestExtension>().unwrap().shut_down);
+        assert!(registry.get_by_type::<TestExtension>().unwrap().initialized);
+        
+        registry.replace(TestExtension::new("fresh")).unwrap();
+        assert!(registry.get("fresh").unwrap().as_any().downcast_ref::<TestExtension>().unwrap().initialized);
+    }
+}
@@ -0,0 +1,138 @@
+//! Filters for deciding which log entries to keep
+
+use crate::core::{LogEntry, LogLevel};
+use crate::util::string_to_log_level;
+
+/// Trait for deciding whether a log entry should be kept
+pub trait Filter {
+    /// Return true if the entry passes the filter
+    fn matches(&self, entry: &LogEntry) -> bool;
+}
+
+impl<F> Filter for F
+where
+    F: Fn(&LogEntry) -> bool,
+{
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self(entry)
+    }
+}
+
+/// Filter that keeps entries at or above a minimum level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelFilter {
+    min_level: LogLevel,
+}
+
+impl LevelFilter {
+    /// Create a filter keeping entries at `min_level` or more severe
+    pub fn new(min_level: LogLevel) -> Self {
+        Self { min_level }
+    }
+    
+    /// Create a filter from the level named in an environment variable
+    ///
+    /// The value is parsed leniently (unknown names mean `Info`); an unset
+    /// or non-unicode variable also defaults to `Info`. The conventional
+    /// variable is `CHRYSALIS_LOG_LEVEL`.
+    pub fn from_env(var: &str) -> Self {
+        match std::env::var(var) {
+            Ok(level) => Self::new(string_to_log_level(level.trim())),
+            Err(_) => Self::default(),
+        }
+    }
+    
+    /// Get the minimum level of this filter
+    pub fn min_level(&self) -> LogLevel {
+        self.min_level
+    }
+}
+
+impl Default for LevelFilter {
+    fn default() -> Self {
+        Self::new(LogLevel::Info)
+    }
+}
+
+impl Filter for LevelFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        entry.level >= self.min_level
+    }
+}
+
+/// Filter keeping entries whose logger name falls under a prefix
+///
+/// Matching follows the dot-separated hierarchy: `com.app` matches
+/// `com.app` and `com.app.db` but not `com.application`. An empty prefix
+/// matches every named logger; entries without a logger name never match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggerFilter {
+    prefix: String,
+}
+
+impl LoggerFilter {
+    /// Create a filter keeping the `prefix` logger and its descendants
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self { prefix: prefix.into() }
+    }
+    
+    /// Get the logger prefix of this filter
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    
+    /// Check whether a logger name falls under the prefix
+    pub fn matches_logger(&self, name: &str) -> bool {
+        match name.strip_prefix(self.prefix.as_str()) {
+            Some(rest) => rest.is_empty() || self.prefix.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
+    }
+}
+
+impl Filter for LoggerFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        entry.metadata.logger
+            .as_deref()
+            .is_some_and(|name| self.matches_logger(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_level_filter_from_env() {
+        let var = "CHRYSALIS_TEST_LEVEL_FROM_ENV";
+        
+        std::env::remove_var(var);
+        assert_eq!(LevelFilter::from_env(var).min_level(), LogLevel::Info);
+        
+        std::env::set_var(var, "warning");
+        let filter = LevelFilter::from_env(var);
+        assert_eq!(filter.min_level(), LogLevel::Warn);
+        assert!(filter.matches(&LogEntry::new("kept", LogLevel::Error)));
+        assert!(!filter.matches(&LogEntry::new("dropped", LogLevel::Info)));
+        
+        std::env::remove_var(var);
+    }
+    
+    #[test]
+    fn test_logger_filter_prefix() {
+        let filter = LoggerFilter::new("com.app");
+        assert!(filter.matches_logger("com.app"));
+        assert!(filter.matches_logger("com.app.db"));
+        assert!(filter.matches_logger("com.app.db.pool"));
+        assert!(!filter.matches_logger("com.application"));
+        assert!(!filter.matches_logger("com"));
+        assert!(!filter.matches_logger("org.app"));
+        
+        let entry = LogEntry::new("Query slow", LogLevel::Warn).with_logger("com.app.db");
+        assert!(filter.matches(&entry));
+        assert!(!LoggerFilter::new("com.app.web").matches(&entry));
+        assert!(!filter.matches(&LogEntry::new("No logger", LogLevel::Warn)));
+        
+        assert!(LoggerFilter::new("").matches(&entry));
+    }
+}
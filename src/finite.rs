@@ -0,0 +1,419 @@
+//! Handling of non-finite floats in context values
+//!
+//! `serde_json` silently turns NaN and infinity into `null`. The serializer
+//! wrapper here intercepts floats at any depth while a value is converted,
+//! so they can be kept as sentinel strings or rejected instead.
+
+use serde::ser::{self, Serialize, Serializer};
+use crate::error::{Error, Result};
+
+/// What to do with NaN and infinite floats in context values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFiniteMode {
+    /// Store `null`, matching plain `serde_json` behavior
+    #[default]
+    Null,
+    /// Store `"NaN"`, `"Infinity"` or `"-Infinity"`
+    Sentinel,
+    /// Fail with `Error::SerializationError`
+    Reject,
+}
+
+/// Sentinel string for a non-finite float
+fn non_finite_sentinel(value: f64) -> &'static str {
+    if value.is_nan() {
+        "NaN"
+    } else if value > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+/// Convert `value` to JSON, treating non-finite floats according to `mode`
+pub fn to_value_finite<T>(value: &T, mode: NonFiniteMode) -> Result<serde_json::Value>
+where
+    T: Serialize + ?Sized,
+{
+    match mode {
+        NonFiniteMode::Null => serde_json::to_value(value),
+        _ => serde_json::to_value(Finite { value, mode }),
+    }
+    .map_err(Error::SerializationError)
+}
+
+/// Serializes the wrapped value through a `FiniteSerializer`
+struct Finite<'a, T: ?Sized> {
+    value: &'a T,
+    mode: NonFiniteMode,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Finite<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.value.serialize(FiniteSerializer { inner: serializer, mode: self.mode })
+    }
+}
+
+/// Serializer forwarding to `inner` except for non-finite floats
+struct FiniteSerializer<S> {
+    inner: S,
+    mode: NonFiniteMode,
+}
+
+impl<S: Serializer> FiniteSerializer<S> {
+    fn float(self, value: f64) -> std::result::Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            return self.inner.serialize_f64(value);
+        }
+        
+        match self.mode {
+            NonFiniteMode::Null => self.inner.serialize_unit(),
+            NonFiniteMode::Sentinel => self.inner.serialize_str(non_finite_sentinel(value)),
+            NonFiniteMode::Reject => Err(ser::Error::custom(format!(
+                "non-finite float {} cannot be represented in JSON", value
+            ))),
+        }
+    }
+}
+
+/// Compound serializer wrapping each element in `Finite`
+struct FiniteCompound<C> {
+    inner: C,
+    mode: NonFiniteMode,
+}
+
+impl<S: Serializer> Serializer for FiniteSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = FiniteCompound<S::SerializeSeq>;
+    type SerializeTuple = FiniteCompound<S::SerializeTuple>;
+    type SerializeTupleStruct = FiniteCompound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = FiniteCompound<S::SerializeTupleVariant>;
+    type SerializeMap = FiniteCompound<S::SerializeMap>;
+    type SerializeStruct = FiniteCompound<S::SerializeStruct>;
+    type SerializeStructVariant = FiniteCompound<S::SerializeStructVariant>;
+    
+    fn serialize_bool(self, v: bool) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_bool(v)
+    }
+    
+    fn serialize_i8(self, v: i8) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_i8(v)
+    }
+    
+    fn serialize_i16(self, v: i16) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_i16(v)
+    }
+    
+    fn serialize_i32(self, v: i32) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_i32(v)
+    }
+    
+    fn serialize_i64(self, v: i64) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_i64(v)
+    }
+    
+    fn serialize_i128(self, v: i128) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_i128(v)
+    }
+    
+    fn serialize_u8(self, v: u8) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_u8(v)
+    }
+    
+    fn serialize_u16(self, v: u16) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_u16(v)
+    }
+    
+    fn serialize_u32(self, v: u32) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_u32(v)
+    }
+    
+    fn serialize_u64(self, v: u64) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_u64(v)
+    }
+    
+    fn serialize_u128(self, v: u128) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_u128(v)
+    }
+    
+    fn serialize_f32(self, v: f32) -> std::result::Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            self.inner.serialize_f32(v)
+        } else {
+            self.float(f64::from(v))
+        }
+    }
+    
+    fn serialize_f64(self, v: f64) -> std::result::Result<S::Ok, S::Error> {
+        self.float(v)
+    }
+    
+    fn serialize_char(self, v: char) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_char(v)
+    }
+    
+    fn serialize_str(self, v: &str) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_str(v)
+    }
+    
+    fn serialize_bytes(self, v: &[u8]) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_bytes(v)
+    }
+    
+    fn serialize_none(self) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+    
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&Finite { value, mode: self.mode })
+    }
+    
+    fn serialize_unit(self) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+    
+    fn serialize_unit_struct(self, name: &'static str) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+    
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, variant_index, variant)
+    }
+    
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(name, &Finite { value, mode: self.mode })
+    }
+    
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_variant(name, variant_index, variant, &Finite { value, mode: self.mode })
+    }
+    
+    fn serialize_seq(self, len: Option<usize>) -> std::result::Result<Self::SerializeSeq, S::Error> {
+        let inner = self.inner.serialize_seq(len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_tuple(self, len: usize) -> std::result::Result<Self::SerializeTuple, S::Error> {
+        let inner = self.inner.serialize_tuple(len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> std::result::Result<Self::SerializeTupleStruct, S::Error> {
+        let inner = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, S::Error> {
+        let inner = self.inner.serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_map(self, len: Option<usize>) -> std::result::Result<Self::SerializeMap, S::Error> {
+        let inner = self.inner.serialize_map(len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> std::result::Result<Self::SerializeStruct, S::Error> {
+        let inner = self.inner.serialize_struct(name, len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, S::Error> {
+        let inner = self.inner.serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(FiniteCompound { inner, mode: self.mode })
+    }
+    
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<C: ser::SerializeSeq> ser::SerializeSeq for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_element(&Finite { value, mode: self.mode })
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTuple> ser::SerializeTuple for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_element(&Finite { value, mode: self.mode })
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_field(&Finite { value, mode: self.mode })
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_field(&Finite { value, mode: self.mode })
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeMap> ser::SerializeMap for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_key(key)
+    }
+    
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_value(&Finite { value, mode: self.mode })
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStruct> ser::SerializeStruct for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_field(key, &Finite { value, mode: self.mode })
+    }
+    
+    fn skip_field(&mut self, key: &'static str) -> std::result::Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStructVariant> ser::SerializeStructVariant for FiniteCompound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+    
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), C::Error> {
+        self.inner.serialize_field(key, &Finite { value, mode: self.mode })
+    }
+    
+    fn skip_field(&mut self, key: &'static str) -> std::result::Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+    
+    fn end(self) -> std::result::Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use serde_json::json;
+    
+    #[derive(serde::Serialize)]
+    struct Reading {
+        sensor: &'static str,
+        values: Vec<f64>,
+        peak: Option<f32>,
+    }
+    
+    #[test]
+    fn test_sentinel_mode_recurses() {
+        let reading = Reading {
+            sensor: "probe",
+            values: vec![1.5, f64::NAN, f64::NEG_INFINITY],
+            peak: Some(f32::INFINITY),
+        };
+        
+        let value = to_value_finite(&reading, NonFiniteMode::Sentinel).unwrap();
+        assert_eq!(value, json!({
+            "sensor": "probe",
+            "values": [1.5, "NaN", "-Infinity"],
+            "peak": "Infinity",
+        }));
+        
+        let nested = BTreeMap::from([("ratio", vec![f64::NAN])]);
+        let value = to_value_finite(&nested, NonFiniteMode::Sentinel).unwrap();
+        assert_eq!(value, json!({"ratio": ["NaN"]}));
+    }
+    
+    #[test]
+    fn test_null_and_reject_modes() {
+        assert_eq!(to_value_finite(&f64::NAN, NonFiniteMode::Null).unwrap(), json!(null));
+        assert_eq!(to_value_finite(&2.5, NonFiniteMode::Reject).unwrap(), json!(2.5));
+        
+        let err = to_value_finite(&vec![1.0, f64::INFINITY], NonFiniteMode::Reject);
+        assert!(matches!(err, Err(Error::SerializationError(_))));
+    }
+}
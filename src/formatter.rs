@@ -1,5 +1,10 @@
+use std::collections::HashMap;
 use serde::Serialize;
+use serde_json::Value;
+use chrono::{DateTime, Utc};
+use crate::core::LogLevel;
 use crate::error::{Result, Error};
+use crate::util::truncate_string_with;
 
 /// Trait for formatting log entries
 pub trait Formatter {
@@ -8,6 +13,18 @@ pub trait Formatter {
     
     /// Format a log entry with custom options
     fn format_with_options<T: Serialize>(&self, entry: &T, options: &FormatterOptions) -> Result<String>;
+    
+    /// Check that an entry serializes cleanly without building the output
+    ///
+    /// The default serializes into a writer that discards all bytes.
+    fn validate<T: Serialize>(&self, entry: &T) -> Result<()> {
+        serde_json::to_writer(std::io::sink(), entry).map_err(Error::SerializationError)
+    }
+    
+    /// MIME type of the formatted output, e.g. for HTTP `Content-Type` headers
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
 }
 
 /// Options for formatting log entries
@@ -23,6 +40,74 @@ pub struct FormatterOptions {
     pub include_context: bool,
     /// Whether to pretty-print the output
     pub pretty_print: bool,
+    /// Whether to add `metadata.timestamp_ms` (epoch millis) next to the
+    /// RFC 3339 timestamp, so clients can sort numerically
+    pub include_timestamp_ms: bool,
+    /// Names used for the top-level message and level keys
+    pub field_names: FieldNameMap,
+    /// Maximum message length in characters, if messages should be truncated
+    pub max_message_length: Option<usize>,
+    /// Marker appended to truncated messages (counts toward the length)
+    pub truncation_marker: String,
+    /// Whether to omit the `context` key when the context is empty
+    pub skip_empty_context: bool,
+    /// Whether to render numeric and boolean context values as strings,
+    /// recursively; nulls are left as `null`
+    pub stringify_scalars: bool,
+    /// IANA name of the timezone `metadata.timestamp` is rendered in, e.g.
+    /// `America/New_York`; UTC if unset
+    ///
+    /// Entries themselves always keep their timestamp in UTC. Formatting
+    /// fails with `Error::FormatterError` if the name is unknown or the
+    /// `timezones` feature is disabled.
+    pub display_timezone: Option<String>,
+    /// Glyphs added as a top-level `glyph` key next to the level, if set
+    pub level_glyphs: Option<LevelGlyphs>,
+}
+
+/// Output names for the top-level `message` and `level` keys
+///
+/// Some ingestion systems expect e.g. `msg` or `severity` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldNameMap {
+    /// Key used for the log message
+    pub message: String,
+    /// Key used for the log level
+    pub level: String,
+}
+
+impl Default for FieldNameMap {
+    fn default() -> Self {
+        Self {
+            message: "message".to_string(),
+            level: "level".to_string(),
+        }
+    }
+}
+
+impl FieldNameMap {
+    /// Create a map with custom message and level key names
+    pub fn new(message: impl Into<String>, level: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            level: level.into(),
+        }
+    }
+    
+    /// Rename the keys of a serialized entry
+    fn apply(&self, value: &mut Value) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        
+        for (from, to) in [("message", &self.message), ("level", &self.level)] {
+            if from != to {
+                if let Some(v) = map.remove(from) {
+                    map.insert(to.clone(), v);
+                }
+            }
+        }
+    }
 }
 
 impl Default for FormatterOptions {
@@ -33,10 +118,178 @@ impl Default for FormatterOptions {
             include_metadata: true,
             include_context: true,
             pretty_print: false,
+            include_timestamp_ms: false,
+            field_names: FieldNameMap::default(),
+            max_message_length: None,
+            truncation_marker: "...".to_string(),
+            skip_empty_context: false,
+            stringify_scalars: false,
+            display_timezone: None,
+            level_glyphs: None,
+        }
+    }
+}
+
+/// Per-level glyph overrides for terminal rendering
+///
+/// Levels without an override use `LogLevel::glyph`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelGlyphs {
+    overrides: HashMap<LogLevel, String>,
+}
+
+impl LevelGlyphs {
+    /// Create a glyph map using the defaults for every level
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Override the glyph of a level
+    pub fn with_glyph(mut self, level: LogLevel, glyph: impl Into<String>) -> Self {
+        self.overrides.insert(level, glyph.into());
+        self
+    }
+    
+    /// Get the glyph for a level
+    pub fn get(&self, level: LogLevel) -> &str {
+        self.overrides.get(&level).map(String::as_str).unwrap_or_else(|| level.glyph())
+    }
+    
+    /// Add a `glyph` key matching the serialized entry's level
+    fn apply(&self, value: &mut Value) {
+        let level = value
+            .get("level")
+            .and_then(Value::as_str)
+            .and_then(|level| level.parse::<LogLevel>().ok());
+        
+        if let (Some(level), Value::Object(map)) = (level, value) {
+            map.insert("glyph".to_string(), Value::String(self.get(level).to_string()));
         }
     }
 }
 
+impl FormatterOptions {
+    /// Whether any option requires rewriting the serialized value
+    fn rewrites_value(&self) -> bool {
+        self.include_timestamp_ms
+            || self.display_timezone.is_some()
+            || self.level_glyphs.is_some()
+            || self.max_message_length.is_some()
+            || self.skip_empty_context
+            || self.stringify_scalars
+            || self.field_names != FieldNameMap::default()
+    }
+}
+
+/// Serialize an entry to a JSON value with the options applied
+fn prepare_value<T: Serialize>(entry: &T, options: &FormatterOptions) -> Result<Value> {
+    let mut value = serde_json::to_value(entry).map_err(Error::SerializationError)?;
+    
+    if options.include_timestamp_ms {
+        add_timestamp_ms(&mut value);
+    }
+    if let Some(tz) = &options.display_timezone {
+        localize_timestamp(&mut value, tz)?;
+    }
+    if let Some(max_length) = options.max_message_length {
+        if let Some(Value::String(message)) = value.get_mut("message") {
+            *message = truncate_string_with(message, max_length, &options.truncation_marker);
+        }
+    }
+    if options.skip_empty_context {
+        if let Value::Object(map) = &mut value {
+            if map.get("context").is_some_and(|c| c.as_object().is_some_and(|c| c.is_empty())) {
+                map.remove("context");
+            }
+        }
+    }
+    if options.stringify_scalars {
+        if let Some(context) = value.get_mut("context") {
+            stringify_scalars(context);
+        }
+    }
+    if let Some(glyphs) = &options.level_glyphs {
+        glyphs.apply(&mut value);
+    }
+    options.field_names.apply(&mut value);
+    
+    Ok(value)
+}
+
+/// Replace numbers and booleans with their string form, recursively
+fn stringify_scalars(value: &mut Value) {
+    match value {
+        Value::Number(n) => *value = Value::String(n.to_string()),
+        Value::Bool(b) => *value = Value::String(b.to_string()),
+        Value::Array(items) => items.iter_mut().for_each(stringify_scalars),
+        Value::Object(map) => map.values_mut().for_each(stringify_scalars),
+        Value::Null | Value::String(_) => {},
+    }
+}
+
+/// Add `metadata.timestamp_ms` derived from `metadata.timestamp`
+fn add_timestamp_ms(value: &mut Value) {
+    let Some(Value::Object(metadata)) = value.get_mut("metadata") else {
+        return;
+    };
+    
+    let millis = metadata
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|ts| ts.parse::<DateTime<Utc>>().ok())
+        .map(|ts| ts.timestamp_millis());
+    
+    if let Some(millis) = millis {
+        metadata.insert("timestamp_ms".to_string(), Value::from(millis));
+    }
+}
+
+/// Re-render `metadata.timestamp` in the named timezone
+#[cfg(feature = "timezones")]
+fn localize_timestamp(value: &mut Value, tz: &str) -> Result<()> {
+    let tz: chrono_tz::Tz = tz
+        .parse()
+        .map_err(|_| Error::FormatterError(format!("Unknown display timezone '{}'", tz)))?;
+    let Some(Value::String(timestamp)) = value.pointer_mut("/metadata/timestamp") else {
+        return Ok(());
+    };
+    
+    if let Ok(parsed) = timestamp.parse::<DateTime<Utc>>() {
+        *timestamp = crate::util::format_timestamp_tz(&parsed, tz);
+    }
+    Ok(())
+}
+
+/// Timezone rendering needs the `timezones` feature
+#[cfg(not(feature = "timezones"))]
+fn localize_timestamp(_value: &mut Value, tz: &str) -> Result<()> {
+    Err(Error::FormatterError(format!(
+        "Display timezone '{}' requires the `timezones` feature", tz
+    )))
+}
+
+/// Format an entry as a compact or pretty string with the options applied
+///
+/// Entries are serialized directly, keeping their field order, unless an
+/// option has to rewrite the output. Rewritten output goes through
+/// `serde_json::Value`, whose object keys are sorted.
+fn format_entry<T: Serialize>(entry: &T, options: &FormatterOptions, pretty: bool) -> Result<String> {
+    if options.rewrites_value() {
+        let value = prepare_value(entry, options)?;
+        return render(&value, pretty);
+    }
+    render(entry, pretty)
+}
+
+/// Render a value as a compact or pretty string
+fn render<T: Serialize + ?Sized>(value: &T, pretty: bool) -> Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(value).map_err(Error::SerializationError)
+    } else {
+        serde_json::to_string(value).map_err(Error::SerializationError)
+    }
+}
+
 /// Simple formatter that outputs JSON
 pub struct SimpleFormatter;
 
@@ -59,16 +312,14 @@ impl Formatter for SimpleFormatter {
     }
     
     fn format_with_options<T: Serialize>(&self, entry: &T, options: &FormatterOptions) -> Result<String> {
-        if options.pretty_print {
-            serde_json::to_string_pretty(entry).map_err(Error::SerializationError)
-        } else {
-            serde_json::to_string(entry).map_err(Error::SerializationError)
-        }
+        format_entry(entry, options, options.pretty_print)
     }
 }
 
 /// Pretty formatter with more options
-#[allow(dead_code)]
+///
+/// `format` always pretty-prints; the stored options' `pretty_print` flag
+/// is ignored there, the others apply.
 pub struct PrettyFormatter {
     options: FormatterOptions,
 }
@@ -98,14 +349,218 @@ impl Default for PrettyFormatter {
 
 impl Formatter for PrettyFormatter {
     fn format<T: Serialize>(&self, entry: &T) -> Result<String> {
-        serde_json::to_string_pretty(entry).map_err(Error::SerializationError)
+        format_entry(entry, &self.options, true)
     }
     
     fn format_with_options<T: Serialize>(&self, entry: &T, options: &FormatterOptions) -> Result<String> {
-        if options.pretty_print {
-            serde_json::to_string_pretty(entry).map_err(Error::SerializationError)
-        } else {
-            serde_json::to_string(entry).map_err(Error::SerializationError)
+        format_entry(entry, options, options.pretty_print)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogEntry;
+    
+    #[test]
+    fn test_content_types() {
+        assert_eq!(SimpleFormatter::new().content_type(), "application/json");
+        assert_eq!(PrettyFormatter::new().content_type(), "application/json");
+    }
+    
+    #[test]
+    fn test_validate() {
+        struct Unserializable;
+        
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("cannot serialize"))
+            }
         }
+        
+        let formatter = SimpleFormatter::new();
+        assert!(formatter.validate(&LogEntry::new("fine", LogLevel::Info)).is_ok());
+        
+        let mut entry = LogEntry::new("fine", LogLevel::Info);
+        entry.add_context("nested", serde_json::json!({"a": [1, 2]})).unwrap();
+        assert!(PrettyFormatter::new().validate(&entry).is_ok());
+        
+        let result = formatter.validate(&Unserializable);
+        assert!(matches!(result, Err(Error::SerializationError(e)) if e.to_string().contains("cannot serialize")));
+    }
+    
+    #[test]
+    fn test_timestamp_ms() {
+        let entry = LogEntry::new("Sorted client-side", LogLevel::Info);
+        let formatter = SimpleFormatter::new();
+        
+        let plain: Value = serde_json::from_str(&formatter.format(&entry).unwrap()).unwrap();
+        assert!(plain["metadata"].get("timestamp_ms").is_none());
+        
+        let options = FormatterOptions {
+            include_timestamp_ms: true,
+            ..Default::default()
+        };
+        let output = formatter.format_with_options(&entry, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        
+        let timestamp: DateTime<Utc> = value["metadata"]["timestamp"].as_str().unwrap().parse().unwrap();
+        assert_eq!(value["metadata"]["timestamp_ms"], timestamp.timestamp_millis());
+        assert_eq!(timestamp, entry.metadata.timestamp);
+    }
+    
+    #[test]
+    fn test_message_truncation_marker() {
+        let entry = LogEntry::new("a very long log message", LogLevel::Info);
+        let options = FormatterOptions {
+            max_message_length: Some(10),
+            truncation_marker: "\u{2026}".to_string(),
+            ..Default::default()
+        };
+        
+        let output = SimpleFormatter::new().format_with_options(&entry, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["message"], "a very lo\u{2026}");
+    }
+    
+    #[test]
+    fn test_skip_empty_context() {
+        let entry = LogEntry::new("Nothing extra", LogLevel::Info);
+        let formatter = SimpleFormatter::new();
+        
+        let default = formatter.format_with_options(&entry, &FormatterOptions::default()).unwrap();
+        assert!(default.contains(r#""context":{}"#));
+        
+        let options = FormatterOptions {
+            skip_empty_context: true,
+            ..Default::default()
+        };
+        let skipped = formatter.format_with_options(&entry, &options).unwrap();
+        assert!(!skipped.contains("context"));
+        
+        let mut with_context = entry.clone();
+        with_context.add_context("k", "v").unwrap();
+        let kept = formatter.format_with_options(&with_context, &options).unwrap();
+        assert!(kept.contains(r#""context":{"k":"v"}"#));
+    }
+    
+    #[test]
+    fn test_stringify_scalars() {
+        let mut entry = LogEntry::new("Typed as text downstream", LogLevel::Info);
+        entry.add_context("enabled", true).unwrap();
+        entry.add_context("count", 42).unwrap();
+        entry.add_context("nested", serde_json::json!({"ratio": 0.5, "tags": [1, "a"], "none": null})).unwrap();
+        
+        let options = FormatterOptions {
+            stringify_scalars: true,
+            ..Default::default()
+        };
+        let output = SimpleFormatter::new().format_with_options(&entry, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        
+        assert_eq!(value["context"]["enabled"], "true");
+        assert_eq!(value["context"]["count"], "42");
+        assert_eq!(value["context"]["nested"]["ratio"], "0.5");
+        assert_eq!(value["context"]["nested"]["tags"], serde_json::json!(["1", "a"]));
+        assert!(value["context"]["nested"]["none"].is_null());
     }
-}
\ No newline at end of file
+    
+    #[cfg(feature = "timezones")]
+    #[test]
+    fn test_display_timezone() {
+        let mut entry = LogEntry::new("Shown in local time", LogLevel::Info);
+        entry.metadata.timestamp = "2024-01-15T12:30:00Z".parse().unwrap();
+        let options = FormatterOptions {
+            display_timezone: Some("America/New_York".to_string()),
+            ..Default::default()
+        };
+        
+        let output = SimpleFormatter::new().format_with_options(&entry, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["metadata"]["timestamp"], "2024-01-15T07:30:00.000-05:00");
+        
+        // The rendered value still denotes the same instant
+        let parsed: DateTime<Utc> = value["metadata"]["timestamp"].as_str().unwrap().parse().unwrap();
+        assert_eq!(parsed, entry.metadata.timestamp);
+        
+        let unknown = FormatterOptions {
+            display_timezone: Some("Mars/Olympus_Mons".to_string()),
+            ..Default::default()
+        };
+        let result = SimpleFormatter::new().format_with_options(&entry, &unknown);
+        assert!(matches!(result, Err(Error::FormatterError(_))));
+    }
+    
+    #[cfg(not(feature = "timezones"))]
+    #[test]
+    fn test_display_timezone_requires_feature() {
+        let entry = LogEntry::new("Shown in local time", LogLevel::Info);
+        let options = FormatterOptions {
+            display_timezone: Some("America/New_York".to_string()),
+            ..Default::default()
+        };
+        
+        let result = SimpleFormatter::new().format_with_options(&entry, &options);
+        assert!(matches!(result, Err(Error::FormatterError(msg)) if msg.contains("timezones")));
+    }
+    
+    #[test]
+    fn test_level_glyph_overrides() {
+        let glyphs = LevelGlyphs::new().with_glyph(LogLevel::Error, "E");
+        assert_eq!(glyphs.get(LogLevel::Error), "E");
+        assert_eq!(glyphs.get(LogLevel::Warn), LogLevel::Warn.glyph());
+        
+        let options = FormatterOptions {
+            level_glyphs: Some(glyphs.clone()),
+            ..Default::default()
+        };
+        let formatter = SimpleFormatter::new();
+        
+        let error = LogEntry::new("Disk full", LogLevel::Error);
+        let output = formatter.format_with_options(&error, &options).unwrap();
+        assert!(output.contains(r#""glyph":"E""#));
+        
+        let warn = LogEntry::new("Disk almost full", LogLevel::Warn);
+        let output = formatter.format_with_options(&warn, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["glyph"], LogLevel::Warn.glyph());
+        
+        assert!(!formatter.format(&error).unwrap().contains("glyph"));
+        
+        let line = error.display_line(&glyphs);
+        assert!(line.starts_with("E [ERROR] "));
+        assert!(line.ends_with("Disk full"));
+    }
+    
+    #[test]
+    fn test_default_options_keep_field_order() {
+        let entry = LogEntry::new("Ordered", LogLevel::Info);
+        let formatter = SimpleFormatter::new();
+        
+        let output = formatter.format_with_options(&entry, &FormatterOptions::default()).unwrap();
+        assert_eq!(output, entry.to_json().unwrap());
+        assert!(output.starts_with(r#"{"message":"Ordered","level":"info""#));
+        
+        let pretty = PrettyFormatter::with_options(FormatterOptions::default());
+        assert_eq!(pretty.format(&entry).unwrap(), entry.to_pretty_json().unwrap());
+    }
+    
+    #[test]
+    fn test_field_name_map() {
+        let entry = LogEntry::new("Renamed keys", LogLevel::Warn);
+        let options = FormatterOptions {
+            field_names: FieldNameMap::new("msg", "severity"),
+            ..Default::default()
+        };
+        
+        let output = SimpleFormatter::new().format_with_options(&entry, &options).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["msg"], "Renamed keys");
+        assert_eq!(value["severity"], "warn");
+        assert!(value.get("message").is_none());
+        assert!(value.get("level").is_none());
+        
+        let default = SimpleFormatter::new().format_with_options(&entry, &FormatterOptions::default()).unwrap();
+        assert!(default.contains(r#""message":"Renamed keys""#));
+    }
+}
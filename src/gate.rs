@@ -0,0 +1,110 @@
+//! Gates deciding whether a log entry should be emitted at all
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::core::LogLevel;
+
+/// Discriminant of the global minimum level; `Trace` lets everything through
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Trace as u8);
+
+/// Check a level against the global minimum before building an entry
+///
+/// A single relaxed atomic load, cheap enough for the hottest paths.
+pub fn should_log(level: LogLevel) -> bool {
+    level as u8 >= MIN_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Set the global minimum level consulted by `should_log`
+pub fn set_min_level(level: LogLevel) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// One-shot gate: lets each key through exactly once
+///
+/// Useful for "log this warning once" semantics. Unlike throttling there is
+/// no time window; a key is blocked forever after its first use (until
+/// `reset`).
+#[derive(Debug, Default)]
+pub struct Once {
+    seen: Mutex<HashSet<String>>,
+}
+
+impl Once {
+    /// Create a gate that has seen no keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Return true only the first time `key` is seen
+    pub fn allow(&self, key: &str) -> bool {
+        match self.seen.lock() {
+            Ok(mut seen) => {
+                if seen.contains(key) {
+                    false
+                } else {
+                    seen.insert(key.to_string())
+                }
+            },
+            Err(poisoned) => poisoned.into_inner().insert(key.to_string()),
+        }
+    }
+    
+    /// Forget all seen keys
+    pub fn reset(&self) {
+        match self.seen.lock() {
+            Ok(mut seen) => seen.clear(),
+            Err(poisoned) => poisoned.into_inner().clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    #[test]
+    fn test_should_log_min_level() {
+        assert!(should_log(LogLevel::Trace));
+        
+        set_min_level(LogLevel::Warn);
+        assert!(!should_log(LogLevel::Debug));
+        assert!(!should_log(LogLevel::Info));
+        assert!(should_log(LogLevel::Warn));
+        assert!(should_log(LogLevel::Fatal));
+        
+        set_min_level(LogLevel::Trace);
+        assert!(should_log(LogLevel::Trace));
+    }
+    
+    #[test]
+    fn test_once_allows_first_use_only() {
+        let once = Once::new();
+        assert!(once.allow("config_missing"));
+        assert!(!once.allow("config_missing"));
+        assert!(once.allow("disk_low"));
+        
+        once.reset();
+        assert!(once.allow("config_missing"));
+    }
+    
+    #[test]
+    fn test_once_is_exclusive_across_threads() {
+        let once = Once::new();
+        let allowed = AtomicUsize::new(0);
+        
+        std::thread::scope(|scope| {
+            for _ in 0..16 {
+                scope.spawn(|| {
+                    if once.allow("startup_warning") {
+                        allowed.fetch_add(1, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+        
+        assert_eq!(allowed.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,331 @@
+//! Asynchronous HTTP sink posting batches of log entries
+
+use std::sync::Mutex;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+
+/// Body encoding used when posting a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpBodyFormat {
+    /// One JSON entry per line (`application/x-ndjson`)
+    #[default]
+    Ndjson,
+    /// A single JSON array of entries (`application/json`)
+    JsonArray,
+}
+
+impl HttpBodyFormat {
+    /// MIME type of the encoded body
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            HttpBodyFormat::Ndjson => "application/x-ndjson",
+            HttpBodyFormat::JsonArray => "application/json",
+        }
+    }
+    
+    fn encode(&self, entries: &[LogEntry]) -> Result<String> {
+        match self {
+            HttpBodyFormat::Ndjson => {
+                let mut body = String::new();
+                for entry in entries {
+                    body.push_str(&entry.to_json()?);
+                    body.push('\n');
+                }
+                Ok(body)
+            },
+            HttpBodyFormat::JsonArray => {
+                serde_json::to_string(entries).map_err(Error::SerializationError)
+            },
+        }
+    }
+}
+
+/// Sink that buffers entries and posts them to an HTTP log API
+///
+/// Both `http://` and `https://` URLs are supported, e.g.
+/// `HttpSink::new("https://logs.example.com/ingest")`; TLS uses rustls.
+///
+/// Buffered entries are sent when `flush` is called or when the buffer
+/// reaches the batch size. A batch that fails to send (transport error or
+/// non-2xx response) is put back at the front of the buffer and the
+/// failure is returned, so a later `flush` retries it. The buffer holds at
+/// most `max_buffered` entries; beyond that the oldest are dropped.
+pub struct HttpSink {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    batch_size: usize,
+    max_buffered: usize,
+    format: HttpBodyFormat,
+    buffer: Mutex<Vec<LogEntry>>,
+}
+
+impl HttpSink {
+    /// Create a sink posting to `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            headers: Vec::new(),
+            batch_size: 100,
+            max_buffered: 10_000,
+            format: HttpBodyFormat::default(),
+            buffer: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Add a header sent with every request, e.g. for authentication
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+    
+    /// Set the number of buffered entries that triggers a send
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+    
+    /// Set the maximum number of entries held while sends are failing
+    ///
+    /// Once exceeded, the oldest buffered entries are dropped. Never lower
+    /// than the batch size.
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered.max(1);
+        self
+    }
+    
+    /// Set the body encoding
+    pub fn with_format(mut self, format: HttpBodyFormat) -> Self {
+        self.format = format;
+        self
+    }
+    
+    /// Number of entries waiting to be sent
+    pub fn buffered(&self) -> usize {
+        self.buffer.lock().map(|buffer| buffer.len()).unwrap_or(0)
+    }
+    
+    /// Buffer an entry, sending the batch once it is full
+    pub async fn emit(&self, entry: &LogEntry) -> Result<()> {
+        let full = {
+            let mut buffer = self.lock_buffer()?;
+            buffer.push(entry.clone());
+            self.drop_oldest(&mut buffer);
+            buffer.len() >= self.batch_size
+        };
+        
+        if full {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+    
+    /// Send all buffered entries
+    ///
+    /// On failure the batch is restored ahead of any entries buffered while
+    /// the request was in flight, dropping the oldest entries if that takes
+    /// the buffer past `max_buffered`.
+    pub async fn flush(&self) -> Result<()> {
+        let batch = std::mem::take(&mut *self.lock_buffer()?);
+        if batch.is_empty() {
+            return Ok(());
+        }
+        
+        let result = self.send(&batch).await;
+        if result.is_err() {
+            let mut buffer = self.lock_buffer()?;
+            buffer.splice(0..0, batch);
+            self.drop_oldest(&mut buffer);
+        }
+        result
+    }
+    
+    /// Trim the buffer to `max_buffered` entries, keeping the newest
+    fn drop_oldest(&self, buffer: &mut Vec<LogEntry>) {
+        let max = self.max_buffered.max(self.batch_size);
+        if buffer.len() > max {
+            buffer.drain(..buffer.len() - max);
+        }
+    }
+    
+    /// Post one batch
+    async fn send(&self, batch: &[LogEntry]) -> Result<()> {
+        let body = self.format.encode(batch)?;
+        let mut request = self.client
+            .post(&self.url)
+            .header("Content-Type", self.format.content_type())
+            .body(body);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::LoggingError(format!("HTTP sink request failed: {}", e)))?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::LoggingError(format!(
+                "HTTP sink received status {} from {}", status, self.url
+            )));
+        }
+        Ok(())
+    }
+    
+    fn lock_buffer(&self) -> Result<std::sync::MutexGuard<'_, Vec<LogEntry>>> {
+        self.buffer
+            .lock()
+            .map_err(|_| Error::LoggingError("HTTP sink lock poisoned".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use crate::core::LogLevel;
+    
+    /// Answer one request per status in order, returning each (headers, body)
+    fn mock_server(statuses: &'static [&'static str]) -> (String, thread::JoinHandle<Vec<(String, String)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/logs", listener.local_addr().unwrap());
+        
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (mut conn, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(conn.try_clone().unwrap());
+                
+                let mut headers = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    headers.push_str(&line);
+                }
+                
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                write!(conn, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+                requests.push((headers, String::from_utf8(body).unwrap()));
+            }
+            requests
+        });
+        
+        (url, handle)
+    }
+    
+    #[tokio::test]
+    async fn test_http_sink_posts_ndjson_batch() {
+        let (url, server) = mock_server(&["200 OK"]);
+        let sink = HttpSink::new(url)
+            .with_header("Authorization", "Bearer secret")
+            .with_batch_size(2);
+        
+        sink.emit(&LogEntry::new("first", LogLevel::Info)).await.unwrap();
+        assert_eq!(sink.buffered(), 1);
+        sink.emit(&LogEntry::new("second", LogLevel::Warn)).await.unwrap();
+        assert_eq!(sink.buffered(), 0);
+        
+        let (headers, body) = server.join().unwrap().remove(0);
+        assert!(headers.to_lowercase().contains("authorization: bearer secret"));
+        assert!(headers.contains("application/x-ndjson"));
+        
+        let entries: Vec<LogEntry> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "first");
+        assert_eq!(entries[1].message, "second");
+    }
+    
+    #[tokio::test]
+    async fn test_http_sink_surfaces_error_status() {
+        let (url, server) = mock_server(&["500 Internal Server Error"]);
+        let sink = HttpSink::new(url).with_format(HttpBodyFormat::JsonArray);
+        
+        sink.emit(&LogEntry::new("kept", LogLevel::Error)).await.unwrap();
+        let result = sink.flush().await;
+        
+        let (_, body) = server.join().unwrap().remove(0);
+        assert!(body.starts_with('['));
+        assert!(matches!(result, Err(Error::LoggingError(msg)) if msg.contains("500")));
+        assert_eq!(sink.buffered(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_http_sink_retries_failed_batch() {
+        let (url, server) = mock_server(&["500 Internal Server Error", "200 OK"]);
+        let sink = HttpSink::new(url).with_batch_size(10);
+        
+        sink.emit(&LogEntry::new("first", LogLevel::Info)).await.unwrap();
+        sink.emit(&LogEntry::new("second", LogLevel::Info)).await.unwrap();
+        assert!(sink.flush().await.is_err());
+        assert_eq!(sink.buffered(), 2);
+        
+        sink.emit(&LogEntry::new("third", LogLevel::Info)).await.unwrap();
+        sink.flush().await.unwrap();
+        assert_eq!(sink.buffered(), 0);
+        
+        let requests = server.join().unwrap();
+        let messages: Vec<String> = requests[1].1
+            .lines()
+            .map(|line| serde_json::from_str::<LogEntry>(line).unwrap().message)
+            .collect();
+        assert_eq!(messages, ["first", "second", "third"]);
+    }
+    
+    #[tokio::test]
+    async fn test_http_sink_drops_oldest_when_full() {
+        let statuses = &["503 Service Unavailable", "503 Service Unavailable", "200 OK"];
+        let (url, server) = mock_server(statuses);
+        let sink = HttpSink::new(url).with_batch_size(2).with_max_buffered(3);
+        
+        sink.emit(&LogEntry::new("first", LogLevel::Info)).await.unwrap();
+        assert!(sink.emit(&LogEntry::new("second", LogLevel::Info)).await.is_err());
+        assert!(sink.emit(&LogEntry::new("third", LogLevel::Info)).await.is_err());
+        assert_eq!(sink.buffered(), 3);
+        
+        // The fourth entry pushes "first" out before the batch is sent
+        sink.emit(&LogEntry::new("fourth", LogLevel::Info)).await.unwrap();
+        assert_eq!(sink.buffered(), 0);
+        
+        let requests = server.join().unwrap();
+        let messages: Vec<String> = requests[2].1
+            .lines()
+            .map(|line| serde_json::from_str::<LogEntry>(line).unwrap().message)
+            .collect();
+        assert_eq!(messages, ["second", "third", "fourth"]);
+    }
+    
+    #[tokio::test]
+    async fn test_http_sink_speaks_tls_to_https_urls() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://{}/logs", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut record_type = [0; 1];
+            conn.read_exact(&mut record_type).unwrap();
+            record_type[0]
+        });
+        
+        let sink = HttpSink::new(url);
+        sink.emit(&LogEntry::new("secure", LogLevel::Info)).await.unwrap();
+        assert!(sink.flush().await.is_err());
+        
+        // 0x16 opens a TLS handshake record
+        assert_eq!(server.join().unwrap(), 0x16);
+    }
+}
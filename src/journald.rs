@@ -0,0 +1,207 @@
+//! systemd journal output
+//!
+//! Entries are sent over the journal's native datagram protocol, so no
+//! link against libsystemd is needed.
+
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+
+use crate::core::{LogEntry, LogLevel};
+use crate::error::Result;
+use crate::sink::Sink;
+
+/// Default path of the journal's native protocol socket
+pub const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Maximum length of a journal field name
+const MAX_FIELD_NAME_LEN: usize = 64;
+
+/// Field names written from the entry itself rather than its context
+const CORE_FIELDS: &[&str] = &[
+    "MESSAGE",
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "CODE_FILE",
+    "CODE_LINE",
+    "CODE_FUNC",
+];
+
+/// Sink that writes entries to the systemd journal
+///
+/// The message becomes `MESSAGE`, the level becomes the syslog `PRIORITY`,
+/// source location maps to `CODE_FILE`/`CODE_LINE`/`CODE_FUNC`, and each
+/// context field is sent as a `KEY=VALUE` field with its name sanitized by
+/// `journal_field_name`. Context fields whose name would clash with one of
+/// those core fields get a `CTX_` prefix. Entries must fit in a single
+/// datagram.
+pub struct JournaldSink {
+    socket: UnixDatagram,
+    path: PathBuf,
+    identifier: Option<String>,
+}
+
+impl JournaldSink {
+    /// Create a sink writing to the default journal socket
+    pub fn new() -> Result<Self> {
+        Self::with_socket_path(JOURNAL_SOCKET)
+    }
+    
+    /// Create a sink writing to a specific socket path
+    pub fn with_socket_path(path: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path: path.into(),
+            identifier: None,
+        })
+    }
+    
+    /// Set the `SYSLOG_IDENTIFIER` attached to every entry
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+}
+
+impl Sink for JournaldSink {
+    /// Send an entry to the journal
+    fn emit(&self, entry: &LogEntry) -> Result<()> {
+        let payload = encode_entry(entry, self.identifier.as_deref());
+        self.socket.send_to(&payload, &self.path)?;
+        Ok(())
+    }
+    
+    /// Flush the sink
+    ///
+    /// Datagrams are unbuffered, so this is a no-op.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Map a log level to a syslog priority (0 = emerg, 7 = debug)
+pub fn journal_priority(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Trace | LogLevel::Debug => 7,
+        LogLevel::Info => 6,
+        LogLevel::Warn => 4,
+        LogLevel::Error => 3,
+        LogLevel::Critical => 2,
+        LogLevel::Fatal => 1,
+    }
+}
+
+/// Turn a context key into a valid journal field name
+///
+/// Journal field names may only contain `A-Z`, `0-9` and `_`, must not
+/// start with `_` (reserved for trusted fields) or a digit, and are at most
+/// 64 characters long. Letters are uppercased, other characters become `_`,
+/// leading underscores are dropped and a leading digit gets an `F_` prefix.
+pub fn journal_field_name(key: &str) -> String {
+    let upper: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    
+    let mut name = upper.trim_start_matches('_').to_string();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "F_");
+    }
+    name.truncate(MAX_FIELD_NAME_LEN);
+    name
+}
+
+/// Encode an entry in the journal native protocol
+fn encode_entry(entry: &LogEntry, identifier: Option<&str>) -> Vec<u8> {
+    let mut payload = Vec::new();
+    
+    push_field(&mut payload, "MESSAGE", &entry.message);
+    push_field(&mut payload, "PRIORITY", &journal_priority(entry.level).to_string());
+    if let Some(identifier) = identifier {
+        push_field(&mut payload, "SYSLOG_IDENTIFIER", identifier);
+    }
+    if let Some(source) = &entry.metadata.source {
+        push_field(&mut payload, "CODE_FILE", source);
+    }
+    if let Some(line) = entry.metadata.line {
+        push_field(&mut payload, "CODE_LINE", &line.to_string());
+    }
+    if let Some(function) = &entry.metadata.function {
+        push_field(&mut payload, "CODE_FUNC", function);
+    }
+    
+    for (key, value) in &entry.context {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let mut name = journal_field_name(key);
+        if CORE_FIELDS.contains(&name.as_str()) {
+            name.insert_str(0, "CTX_");
+        }
+        push_field(&mut payload, &name, &value);
+    }
+    
+    payload
+}
+
+/// Append one field, using the length-prefixed form for multi-line values
+fn push_field(payload: &mut Vec<u8>, name: &str, value: &str) {
+    payload.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        payload.push(b'\n');
+        payload.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        payload.push(b'=');
+    }
+    payload.extend_from_slice(value.as_bytes());
+    payload.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_journal_field_name_sanitization() {
+        assert_eq!(journal_field_name("user_id"), "USER_ID");
+        assert_eq!(journal_field_name("http.status-code"), "HTTP_STATUS_CODE");
+        assert_eq!(journal_field_name("_hostname"), "HOSTNAME");
+        assert_eq!(journal_field_name("2fa"), "F_2FA");
+        assert_eq!(journal_field_name("___"), "F_");
+        assert_eq!(journal_field_name(&"k".repeat(100)).len(), 64);
+    }
+    
+    #[test]
+    fn test_encode_entry() {
+        let mut entry = LogEntry::new("Disk almost full", LogLevel::Warn);
+        entry.add_context("mount.point", "/var").unwrap();
+        entry.add_context("detail", "line one\nline two").unwrap();
+        
+        let payload = encode_entry(&entry, Some("chrysalis"));
+        let text = String::from_utf8_lossy(&payload);
+        assert!(text.starts_with("MESSAGE=Disk almost full\nPRIORITY=4\nSYSLOG_IDENTIFIER=chrysalis\n"));
+        assert!(text.contains("MOUNT_POINT=/var\n"));
+        
+        let mut multiline = b"DETAIL\n".to_vec();
+        multiline.extend_from_slice(&17u64.to_le_bytes());
+        multiline.extend_from_slice(b"line one\nline two\n");
+        assert!(payload.windows(multiline.len()).any(|w| w == multiline.as_slice()));
+    }
+    
+    #[test]
+    fn test_context_does_not_shadow_core_fields() {
+        let mut entry = LogEntry::new("Upload failed", LogLevel::Error).with_source("upload.rs", 7);
+        entry.add_context("message", "from context").unwrap();
+        entry.add_context("priority", "high").unwrap();
+        entry.add_context("code.file", "other.rs").unwrap();
+        
+        let payload = encode_entry(&entry, None);
+        let text = String::from_utf8_lossy(&payload);
+        assert_eq!(text.lines().filter(|line| line.starts_with("MESSAGE=")).count(), 1);
+        assert!(text.starts_with("MESSAGE=Upload failed\nPRIORITY=3\n"));
+        assert!(text.contains("\nCODE_FILE=upload.rs\n"));
+        assert!(text.contains("\nCTX_MESSAGE=from context\n"));
+        assert!(text.contains("\nCTX_PRIORITY=high\n"));
+        assert!(text.contains("\nCTX_CODE_FILE=other.rs\n"));
+    }
+}
@@ -31,10 +31,62 @@ pub mod error;
 mod formatter;
 mod adapter;
 mod extensions;
-mod util;
+pub mod util;
+mod ndjson;
+mod pool;
+mod filter;
+mod redact;
+mod pipeline;
+mod value_formatter;
+mod sampling;
+mod sink;
+mod stats;
+mod schema;
+mod gate;
+mod enrich;
+mod borrowed;
+mod checksum;
+mod promote;
+mod finite;
+#[cfg(all(feature = "journald", target_os = "linux"))]
+mod journald;
+#[cfg(feature = "tracing")]
+mod tracing_adapter;
+#[cfg(feature = "tracing-subscriber")]
+mod tracing_layer;
+#[cfg(feature = "http")]
+mod http_sink;
+#[cfg(feature = "signing")]
+mod signing;
 
-pub use core::{LogEntry, LogLevel, Serializable, MetaData};
+pub use core::{LogEntry, LogLevel, Serializable, MetaData, ContextScope, SpanStatus, DurationFormat, EmbeddedJson};
 pub use error::Error;
-pub use formatter::{Formatter, SimpleFormatter, PrettyFormatter};
-pub use adapter::{Adapter, StandardAdapter, AdapterOptions};
+pub use formatter::{Formatter, FormatterOptions, FieldNameMap, LevelGlyphs, SimpleFormatter, PrettyFormatter};
+pub use adapter::{Adapter, StandardAdapter, StandardLog, AdapterOptions, DefaultContext, LineAdapter, Stream, MultiLineAssembler};
 pub use extensions::{Extension, ExtensionRegistry};
+pub use ndjson::NdjsonReader;
+pub use pool::{LogEntryPool, PooledEntry};
+pub use filter::{Filter, LevelFilter, LoggerFilter};
+pub use redact::{IpAnonymizer, Redactor, RedactedDebug};
+pub use pipeline::{EntryProcessor, LogPipeline, ProcessStats};
+pub use value_formatter::ValueFormatterRegistry;
+pub use sampling::SamplingExtension;
+pub use sink::{Sink, TcpSink, WriterSink, NullSink, MultiSink, CaptureSink};
+pub use stats::{SizeHistogram, SeverityTracker};
+pub use schema::{ContextSchema, JsonType};
+pub use gate::{Once, should_log, set_min_level};
+pub use enrich::{LevelEnricher, EnricherRegistry};
+pub use borrowed::{LogEntryRef, ContextValueRef};
+pub use checksum::ChecksumExtension;
+pub use promote::{LevelPromoter, PromotionRule};
+pub use finite::{NonFiniteMode, to_value_finite};
+#[cfg(all(feature = "journald", target_os = "linux"))]
+pub use journald::{JournaldSink, journal_field_name, journal_priority};
+#[cfg(feature = "tracing")]
+pub use tracing_adapter::{TracingAdapter, ContextVisitor};
+#[cfg(feature = "tracing-subscriber")]
+pub use tracing_layer::ChrysalisLayer;
+#[cfg(feature = "http")]
+pub use http_sink::{HttpSink, HttpBodyFormat};
+#[cfg(feature = "signing")]
+pub use signing::Signer;
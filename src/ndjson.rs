@@ -0,0 +1,99 @@
+//! Newline-delimited JSON (NDJSON) support
+//!
+//! NDJSON stores one serialized log entry per line, which makes it easy to
+//! append to and to process large files lazily.
+
+use std::io::BufRead;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+
+/// Lazy reader yielding one `LogEntry` per NDJSON line
+///
+/// Blank lines are skipped. A malformed line yields an `Error::ParseError`
+/// for that line only; iteration continues with the next line.
+pub struct NdjsonReader<R: BufRead> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    done: bool,
+}
+
+impl<R: BufRead> NdjsonReader<R> {
+    /// Create a new reader over the given buffered input
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            done: false,
+        }
+    }
+    
+    /// Number of lines consumed so far
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+    
+    /// Consume the reader, returning the underlying input
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonReader<R> {
+    type Item = Result<LogEntry>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+                    let trimmed = self.buffer.trim();
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    
+                    let line = self.line;
+                    return Some(serde_json::from_str(trimmed)
+                        .map_err(|source| Error::ParseError { line, source }));
+                },
+                Err(e) => {
+                    // The input can't make further progress after an I/O failure
+                    self.done = true;
+                    return Some(Err(Error::IoError(e)));
+                },
+            }
+        }
+        
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::core::LogLevel;
+    
+    #[test]
+    fn test_reader_isolates_malformed_line() {
+        let first = LogEntry::new("first", LogLevel::Info).to_json().unwrap();
+        let third = LogEntry::new("third", LogLevel::Warn).to_json().unwrap();
+        let input = format!("{}\n\n{{not json}}\n{}\n", first, third);
+        
+        let results: Vec<_> = NdjsonReader::new(Cursor::new(input)).collect();
+        assert_eq!(results.len(), 3);
+        
+        assert_eq!(results[0].as_ref().unwrap().message, "first");
+        match &results[1] {
+            Err(Error::ParseError { line, .. }) => assert_eq!(*line, 3),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+        let last = results[2].as_ref().unwrap();
+        assert_eq!(last.message, "third");
+        assert_eq!(last.level, LogLevel::Warn);
+    }
+}
@@ -0,0 +1,167 @@
+//! Declarative processing chains over streams of log entries
+
+use crate::core::LogEntry;
+use crate::error::Result;
+use crate::filter::Filter;
+use crate::formatter::Formatter;
+use crate::redact::Redactor;
+
+/// Filters, redacts and formats individual log entries
+///
+/// The processing steps of a `LogPipeline`, usable on their own for entries
+/// that don't come from an iterator (see `util::process_ndjson`).
+pub struct EntryProcessor<F> {
+    filters: Vec<Box<dyn Filter>>,
+    redactor: Option<Redactor>,
+    formatter: F,
+}
+
+impl<F: Formatter> EntryProcessor<F> {
+    /// Create a processor formatting every entry with `formatter`
+    pub fn new(formatter: F) -> Self {
+        Self {
+            filters: Vec::new(),
+            redactor: None,
+            formatter,
+        }
+    }
+    
+    /// Add a filter that entries must pass
+    pub fn filter<P: Filter + 'static>(mut self, filter: P) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+    
+    /// Redact entries before formatting
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+    
+    /// Run a single entry through the filters, redactor and formatter
+    ///
+    /// Returns `None` if a filter rejected the entry.
+    pub fn apply(&self, mut entry: LogEntry) -> Option<Result<String>> {
+        if !self.filters.iter().all(|f| f.matches(&entry)) {
+            return None;
+        }
+        
+        if let Some(redactor) = &self.redactor {
+            redactor.redact(&mut entry);
+        }
+        
+        Some(self.formatter.format(&entry))
+    }
+}
+
+/// Iterator adaptor that filters, redacts and formats log entries
+///
+/// Entries rejected by any filter are skipped; the remaining entries are
+/// redacted (if a redactor is set) and yielded as formatted strings.
+pub struct LogPipeline<I, F> {
+    entries: I,
+    processor: EntryProcessor<F>,
+}
+
+impl<I, F> LogPipeline<I, F>
+where
+    I: Iterator<Item = LogEntry>,
+    F: Formatter,
+{
+    /// Create a pipeline formatting every entry of `entries`
+    pub fn new<T>(entries: T, formatter: F) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
+        Self::with_processor(entries, EntryProcessor::new(formatter))
+    }
+    
+    /// Create a pipeline running `entries` through an existing processor
+    pub fn with_processor<T>(entries: T, processor: EntryProcessor<F>) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+    {
+        Self {
+            entries: entries.into_iter(),
+            processor,
+        }
+    }
+    
+    /// Add a filter that entries must pass
+    pub fn filter<P: Filter + 'static>(mut self, filter: P) -> Self {
+        self.processor = self.processor.filter(filter);
+        self
+    }
+    
+    /// Redact entries before formatting
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.processor = self.processor.redactor(redactor);
+        self
+    }
+}
+
+/// Line counts reported by `util::process_ndjson`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessStats {
+    /// Non-blank lines read
+    pub read: usize,
+    /// Entries written to the output
+    pub written: usize,
+    /// Entries rejected by a filter
+    pub filtered: usize,
+    /// Lines that failed to parse or format
+    pub errored: usize,
+}
+
+impl<I, F> Iterator for LogPipeline<I, F>
+where
+    I: Iterator<Item = LogEntry>,
+    F: Formatter,
+{
+    type Item = Result<String>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        for entry in self.entries.by_ref() {
+            if let Some(result) = self.processor.apply(entry) {
+                return Some(result);
+            }
+        }
+        
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    use crate::filter::LevelFilter;
+    use crate::formatter::SimpleFormatter;
+    
+    #[test]
+    fn test_pipeline_filters_redacts_and_formats() {
+        let levels = [
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Trace,
+            LogLevel::Error,
+        ];
+        let entries = levels.iter().enumerate().map(|(i, level)| {
+            let mut entry = LogEntry::new(format!("entry {}", i), *level);
+            entry.add_context("password", "hunter2").unwrap();
+            entry
+        });
+        
+        let output: Vec<String> = LogPipeline::new(entries, SimpleFormatter::new())
+            .filter(LevelFilter::new(LogLevel::Warn))
+            .redactor(Redactor::new(["password"]))
+            .collect::<Result<_>>()
+            .unwrap();
+        
+        assert_eq!(output.len(), 2);
+        assert!(output[0].contains("entry 2"));
+        assert!(output[1].contains("entry 4"));
+        assert!(output.iter().all(|line| !line.contains("hunter2")));
+    }
+}
@@ -0,0 +1,177 @@
+//! Object pool for reusing `LogEntry` allocations
+//!
+//! High-throughput services can create and drop a very large number of
+//! entries. The pool keeps released entries around so their message buffer
+//! and context map capacity are reused by the next `acquire`.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use crate::core::{LogEntry, LogLevel, MetaData};
+use crate::finite::NonFiniteMode;
+
+/// Default number of idle entries retained by a pool
+const DEFAULT_MAX_IDLE: usize = 1024;
+
+/// Thread-safe pool of reusable log entries
+pub struct LogEntryPool {
+    idle: Mutex<Vec<LogEntry>>,
+    max_idle: usize,
+}
+
+impl Default for LogEntryPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogEntryPool {
+    /// Create a new pool with the default idle capacity
+    pub fn new() -> Self {
+        Self::with_max_idle(DEFAULT_MAX_IDLE)
+    }
+    
+    /// Create a pool retaining at most `max_idle` released entries
+    pub fn with_max_idle(max_idle: usize) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+        }
+    }
+    
+    /// Acquire an entry from the pool
+    ///
+    /// The entry has an empty message and context, `Info` level and fresh
+    /// metadata. It returns to the pool when the guard is dropped.
+    pub fn acquire(&self) -> PooledEntry<'_> {
+        let entry = self.idle
+            .lock()
+            .ok()
+            .and_then(|mut idle| idle.pop())
+            .map(|mut entry| {
+                reset_entry(&mut entry);
+                entry
+            })
+            .unwrap_or_else(|| LogEntry::new(String::new(), LogLevel::Info));
+        
+        PooledEntry {
+            pool: self,
+            entry: Some(entry),
+        }
+    }
+    
+    /// Number of idle entries currently held by the pool
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().map(|idle| idle.len()).unwrap_or(0)
+    }
+    
+    fn release(&self, entry: LogEntry) {
+        if let Ok(mut idle) = self.idle.lock() {
+            if idle.len() < self.max_idle {
+                idle.push(entry);
+            }
+        }
+    }
+}
+
+/// Clear an entry for reuse while keeping its allocations
+fn reset_entry(entry: &mut LogEntry) {
+    entry.message.clear();
+    entry.body = None;
+    entry.level = LogLevel::Info;
+    entry.context.clear();
+    entry.provenance.clear();
+    entry.max_context_fields = None;
+    entry.non_finite = NonFiniteMode::Null;
+    
+    let mut custom = std::mem::take(&mut entry.metadata.custom);
+    custom.clear();
+    entry.metadata = MetaData {
+        custom,
+        ..MetaData::default()
+    };
+}
+
+/// Guard for an entry borrowed from a `LogEntryPool`
+pub struct PooledEntry<'a> {
+    pool: &'a LogEntryPool,
+    entry: Option<LogEntry>,
+}
+
+impl PooledEntry<'_> {
+    /// Detach the entry from the pool, keeping it
+    pub fn into_inner(mut self) -> LogEntry {
+        self.entry.take().expect("pooled entry already taken")
+    }
+}
+
+impl Deref for PooledEntry<'_> {
+    type Target = LogEntry;
+    
+    fn deref(&self) -> &LogEntry {
+        self.entry.as_ref().expect("pooled entry already taken")
+    }
+}
+
+impl DerefMut for PooledEntry<'_> {
+    fn deref_mut(&mut self) -> &mut LogEntry {
+        self.entry.as_mut().expect("pooled entry already taken")
+    }
+}
+
+impl Drop for PooledEntry<'_> {
+    fn drop(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.pool.release(entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reacquired_entry_is_cleared() {
+        let pool = LogEntryPool::new();
+        
+        let capacity = {
+            let mut entry = pool.acquire();
+            entry.message.push_str("first use");
+            entry.level = LogLevel::Error;
+            for i in 0..32 {
+                entry.add_context(format!("field_{}", i), i).unwrap();
+            }
+            entry.context.capacity()
+        };
+        assert_eq!(pool.idle_count(), 1);
+        
+        let entry = pool.acquire();
+        assert!(entry.message.is_empty());
+        assert_eq!(entry.level, LogLevel::Info);
+        assert!(entry.context.is_empty());
+        assert!(entry.context.capacity() >= capacity);
+    }
+    
+    #[test]
+    fn test_concurrent_acquire() {
+        let pool = LogEntryPool::new();
+        
+        std::thread::scope(|scope| {
+            for t in 0..8 {
+                let pool = &pool;
+                scope.spawn(move || {
+                    for i in 0..100 {
+                        let mut entry = pool.acquire();
+                        assert!(entry.context.is_empty());
+                        entry.add_context("thread", t).unwrap();
+                        entry.add_context("iteration", i).unwrap();
+                    }
+                });
+            }
+        });
+        
+        assert!(pool.idle_count() >= 1);
+        assert!(pool.idle_count() <= 8);
+    }
+}
@@ -0,0 +1,87 @@
+//! Content-based level promotion
+
+use crate::core::{LogEntry, LogLevel};
+
+/// Rule raising entries whose message contains `substring` to `min_level`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromotionRule {
+    /// Case-sensitive text to look for in the message
+    pub substring: String,
+    /// Level matching entries are raised to
+    pub min_level: LogLevel,
+}
+
+/// Raises the level of entries whose message matches a rule
+///
+/// Promotion only ever raises a level: an entry already at or above a
+/// matching rule's `min_level` is left alone. When several rules match,
+/// the highest `min_level` wins.
+#[derive(Debug, Clone, Default)]
+pub struct LevelPromoter {
+    rules: Vec<PromotionRule>,
+}
+
+impl LevelPromoter {
+    /// Create a promoter without rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add a rule
+    pub fn with_rule(mut self, substring: impl Into<String>, min_level: LogLevel) -> Self {
+        self.rules.push(PromotionRule {
+            substring: substring.into(),
+            min_level,
+        });
+        self
+    }
+    
+    /// Raise the entry's level if its message matches a rule
+    ///
+    /// Returns whether the level was changed.
+    pub fn promote(&self, entry: &mut LogEntry) -> bool {
+        let target = self.rules
+            .iter()
+            .filter(|rule| entry.message.contains(&rule.substring))
+            .map(|rule| rule.min_level)
+            .max();
+        
+        match target {
+            Some(level) if level > entry.level => {
+                entry.level = level;
+                true
+            },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_promote_matching_entries_only() {
+        let promoter = LevelPromoter::new()
+            .with_rule("failed", LogLevel::Error)
+            .with_rule("retrying", LogLevel::Warn);
+        
+        let mut failed = LogEntry::new("operation failed", LogLevel::Info);
+        assert!(promoter.promote(&mut failed));
+        assert_eq!(failed.level, LogLevel::Error);
+        
+        let mut benign = LogEntry::new("operation succeeded", LogLevel::Info);
+        assert!(!promoter.promote(&mut benign));
+        assert_eq!(benign.level, LogLevel::Info);
+        
+        // Never demotes
+        let mut fatal = LogEntry::new("retrying after failure", LogLevel::Fatal);
+        assert!(!promoter.promote(&mut fatal));
+        assert_eq!(fatal.level, LogLevel::Fatal);
+        
+        // Highest matching rule wins
+        let mut both = LogEntry::new("retrying: connect failed", LogLevel::Debug);
+        promoter.promote(&mut both);
+        assert_eq!(both.level, LogLevel::Error);
+    }
+}
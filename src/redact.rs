@@ -0,0 +1,269 @@
+//! Redaction of sensitive values in log context
+
+use std::collections::HashSet;
+use serde_json::Value;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+use crate::util::anonymize_ip;
+
+/// Default replacement text for redacted values
+pub const DEFAULT_MASK: &str = "[REDACTED]";
+
+/// Split a redaction pointer into (is context, field key, pointer within the field)
+fn parse_redact_pointer(pointer: &str) -> Result<(bool, String, &str)> {
+    let invalid = || Error::LoggingError(format!(
+        "Cannot redact '{}': pointers must target a context or custom metadata field", pointer
+    ));
+    
+    let mut parts = pointer.strip_prefix('/').ok_or_else(invalid)?.splitn(2, '/');
+    let in_context = match parts.next() {
+        Some("context") => true,
+        Some("metadata") => false,
+        _ => return Err(invalid()),
+    };
+    let rest = parts.next().ok_or_else(invalid)?;
+    let (key, nested) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    Ok((in_context, key.replace("~1", "/").replace("~0", "~"), nested))
+}
+
+/// Masks the values of sensitive context keys
+///
+/// Keys are matched case-insensitively at any depth of the context.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    keys: HashSet<String>,
+    mask: String,
+}
+
+impl Redactor {
+    /// Create a redactor for the given sensitive keys
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            keys: keys.into_iter().map(|k| k.as_ref().to_lowercase()).collect(),
+            mask: DEFAULT_MASK.to_string(),
+        }
+    }
+    
+    /// Use a custom replacement text
+    pub fn with_mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+    
+    /// Check if a key is considered sensitive
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        self.keys.contains(&key.to_lowercase())
+    }
+    
+    /// Mask all sensitive values in the entry's context
+    pub fn redact(&self, entry: &mut LogEntry) {
+        for (key, value) in entry.context.iter_mut() {
+            self.redact_pair(key, value);
+        }
+    }
+    
+    /// Mask the values at exact JSON pointer locations in the entry
+    ///
+    /// Pointers are relative to the serialized entry and must point into a
+    /// context field (`/context/card/number`) or a custom metadata field
+    /// (`/metadata/api_key`); the entry is edited in place. Any other
+    /// pointer, such as `/level`, fails before anything is masked. Paths
+    /// that don't exist, including typed metadata fields, are ignored.
+    /// Returns the number of values masked.
+    pub fn redact_paths(&self, entry: &mut LogEntry, pointers: &[&str]) -> Result<usize> {
+        let targets = pointers
+            .iter()
+            .map(|pointer| parse_redact_pointer(pointer))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let mut masked = 0;
+        for (in_context, key, rest) in targets {
+            let fields = if in_context { &mut entry.context } else { &mut entry.metadata.custom };
+            if let Some(target) = fields.get_mut(&key).and_then(|value| value.pointer_mut(rest)) {
+                *target = Value::String(self.mask.clone());
+                masked += 1;
+            }
+        }
+        Ok(masked)
+    }
+    
+    fn redact_pair(&self, key: &str, value: &mut Value) {
+        if self.is_sensitive(key) {
+            *value = Value::String(self.mask.clone());
+        } else {
+            self.redact_value(value);
+        }
+    }
+    
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, nested) in map.iter_mut() {
+                    self.redact_pair(key, nested);
+                }
+            },
+            Value::Array(arr) => {
+                for item in arr.iter_mut() {
+                    self.redact_value(item);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// `Debug`/`Display` view of an entry with sensitive values masked
+///
+/// Returned by `LogEntry::redacted_debug`. Values of sensitive keys in the
+/// context and in custom metadata are masked before printing; the entry
+/// itself is not modified.
+pub struct RedactedDebug<'a> {
+    entry: &'a LogEntry,
+    redactor: Redactor,
+}
+
+impl<'a> RedactedDebug<'a> {
+    /// Create a view of `entry` masking keys with `redactor`
+    pub fn new(entry: &'a LogEntry, redactor: Redactor) -> Self {
+        Self { entry, redactor }
+    }
+    
+    /// Copy of the entry with sensitive values masked
+    fn masked(&self) -> LogEntry {
+        let mut entry = self.entry.clone();
+        self.redactor.redact(&mut entry);
+        for (key, value) in entry.metadata.custom.iter_mut() {
+            self.redactor.redact_pair(key, value);
+        }
+        entry
+    }
+}
+
+impl std::fmt::Debug for RedactedDebug<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.masked(), f)
+    }
+}
+
+impl std::fmt::Display for RedactedDebug<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.masked(), f)
+    }
+}
+
+/// Anonymizes IP addresses found in context values
+///
+/// Every string value (at any depth) that parses as an IP address is
+/// replaced by its anonymized form; see `util::anonymize_ip`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IpAnonymizer;
+
+impl IpAnonymizer {
+    /// Create a new IP anonymizer
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// Anonymize all IP-shaped values in the entry's context
+    pub fn anonymize(&self, entry: &mut LogEntry) {
+        for value in entry.context.values_mut() {
+            anonymize_value(value);
+        }
+    }
+}
+
+fn anonymize_value(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = anonymize_ip(s),
+        Value::Object(map) => map.values_mut().for_each(anonymize_value),
+        Value::Array(arr) => arr.iter_mut().for_each(anonymize_value),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    
+    #[test]
+    fn test_redact_paths() {
+        let mut entry = LogEntry::new("Checkout", LogLevel::Info);
+        entry.add_context("card", serde_json::json!({"number": "4111111111111111", "brand": "visa"})).unwrap();
+        entry.add_context("order", serde_json::json!({"number": "ORD-1001"})).unwrap();
+        
+        let redactor = Redactor::new(Vec::<String>::new());
+        let masked = redactor
+            .redact_paths(&mut entry, &["/context/card/number", "/context/missing"])
+            .unwrap();
+        
+        assert_eq!(masked, 1);
+        assert_eq!(entry.context["card"]["number"], DEFAULT_MASK);
+        assert_eq!(entry.context["card"]["brand"], "visa");
+        assert_eq!(entry.context["order"]["number"], "ORD-1001");
+    }
+    
+    #[test]
+    fn test_redact_paths_stays_out_of_typed_fields() {
+        let mut entry = LogEntry::new("Checkout", LogLevel::Error);
+        entry.add_context("a/b", "slash").unwrap();
+        entry.metadata.add_field("api_key", "sk-live-123").unwrap();
+        entry.metadata.source = Some("src/pay.rs".to_string());
+        
+        let redactor = Redactor::new(Vec::<String>::new());
+        let masked = redactor
+            .redact_paths(&mut entry, &["/metadata/api_key", "/metadata/source", "/context/a~1b"])
+            .unwrap();
+        assert_eq!(masked, 2);
+        assert_eq!(entry.metadata.custom["api_key"], DEFAULT_MASK);
+        assert_eq!(entry.context["a/b"], DEFAULT_MASK);
+        assert_eq!(entry.metadata.source.as_deref(), Some("src/pay.rs"));
+        
+        for pointer in ["/level", "/message", "/context", "context/a"] {
+            assert!(redactor.redact_paths(&mut entry, &["/metadata/api_key", pointer]).is_err());
+        }
+        assert_eq!(entry.level, LogLevel::Error);
+    }
+    
+    #[test]
+    fn test_ip_anonymizer() {
+        let mut entry = LogEntry::new("Login", LogLevel::Info);
+        entry.add_context("ip_address", "192.168.1.42").unwrap();
+        entry.add_context("client", serde_json::json!({"ips": ["10.0.0.7", "::1"]})).unwrap();
+        entry.add_context("user", "alice").unwrap();
+        
+        IpAnonymizer::new().anonymize(&mut entry);
+        
+        assert_eq!(entry.context["ip_address"], "192.168.1.0");
+        assert_eq!(entry.context["client"]["ips"], serde_json::json!(["10.0.0.0", "::"]));
+        assert_eq!(entry.context["user"], "alice");
+    }
+    
+    #[test]
+    fn test_redacted_debug() {
+        let mut entry = LogEntry::new("Login attempt", LogLevel::Warn);
+        entry.add_context("user", "ada").unwrap();
+        entry.add_context("password", "hunter2").unwrap();
+        entry.metadata.add_field("api_key", "sk-live-123").unwrap();
+        
+        let debug = format!("{:?}", entry.redacted_debug(["password", "api_key"]));
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("sk-live-123"));
+        assert!(debug.contains(DEFAULT_MASK));
+        assert!(debug.contains("ada"));
+        
+        let display = entry.redacted_debug(["password"]).to_string();
+        assert!(!display.contains("hunter2"));
+        
+        // The entry itself is untouched
+        assert_eq!(entry.context["password"], "hunter2");
+    }
+}
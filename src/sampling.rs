@@ -0,0 +1,144 @@
+//! Sampling of log entries to reduce volume
+
+use std::any::Any;
+use rand::Rng;
+
+use crate::core::LogEntry;
+use crate::error::Result;
+use crate::extensions::Extension;
+use crate::util::simple_hash;
+
+/// Context field used to group entries for consistent sampling
+pub const TRACE_ID_FIELD: &str = "trace_id";
+
+/// Resolution of the hash-based sampling decision
+const SAMPLING_BUCKETS: u64 = 10_000;
+
+/// Extension that keeps a configurable fraction of log entries
+///
+/// In consistent mode the keep/drop decision for an entry carrying a
+/// `trace_id` context field is derived from that id, so all entries of a
+/// trace are kept or dropped together. Entries without a trace id are
+/// sampled randomly.
+pub struct SamplingExtension {
+    enabled: bool,
+    rate: f64,
+    consistent: bool,
+}
+
+impl SamplingExtension {
+    /// Create a sampler keeping `rate` (0.0 to 1.0) of all entries
+    pub fn new(rate: f64) -> Self {
+        Self {
+            enabled: true,
+            rate: rate.clamp(0.0, 1.0),
+            consistent: false,
+        }
+    }
+    
+    /// Enable or disable consistent per-trace sampling
+    pub fn with_consistent_sampling(mut self, consistent: bool) -> Self {
+        self.consistent = consistent;
+        self
+    }
+    
+    /// Get the sampling rate
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+    
+    /// Decide whether an entry should be kept
+    ///
+    /// Always keeps entries while the extension is disabled.
+    pub fn should_keep(&self, entry: &LogEntry) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        
+        if self.consistent {
+            if let Some(trace_id) = entry.context.get(TRACE_ID_FIELD) {
+                let key = match trace_id.as_str() {
+                    Some(s) => s.to_string(),
+                    None => trace_id.to_string(),
+                };
+                let bucket = simple_hash(&key) % SAMPLING_BUCKETS;
+                return (bucket as f64) < self.rate * SAMPLING_BUCKETS as f64;
+            }
+        }
+        
+        rand::rng().random::<f64>() < self.rate
+    }
+}
+
+impl Extension for SamplingExtension {
+    fn name(&self) -> &str {
+        "sampling"
+    }
+    
+    fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+    
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+    
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    
+    fn traced_entry(trace_id: &str, i: usize) -> LogEntry {
+        let mut entry = LogEntry::new(format!("span event {}", i), LogLevel::Info);
+        entry.add_context(TRACE_ID_FIELD, trace_id).unwrap();
+        entry
+    }
+    
+    #[test]
+    fn test_consistent_sampling_per_trace() {
+        let sampler = SamplingExtension::new(0.5).with_consistent_sampling(true);
+        
+        let mut kept_traces = 0;
+        for t in 0..50 {
+            let trace_id = format!("trace-{}", t);
+            let first = sampler.should_keep(&traced_entry(&trace_id, 0));
+            for i in 1..20 {
+                assert_eq!(sampler.should_keep(&traced_entry(&trace_id, i)), first);
+            }
+            if first {
+                kept_traces += 1;
+            }
+        }
+        
+        // Decisions differ between traces rather than being all-or-nothing
+        assert!(kept_traces > 0 && kept_traces < 50);
+    }
+    
+    #[test]
+    fn test_rate_bounds() {
+        let entry = LogEntry::new("untraced", LogLevel::Info);
+        assert!(SamplingExtension::new(1.0).should_keep(&entry));
+        assert!(!SamplingExtension::new(0.0).should_keep(&entry));
+        
+        let mut disabled = SamplingExtension::new(0.0);
+        disabled.set_enabled(false);
+        assert!(disabled.should_keep(&entry));
+    }
+}
@@ -0,0 +1,151 @@
+//! Type enforcement for context fields
+
+use std::collections::HashMap;
+use serde_json::Value;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+
+/// Expected JSON type of a context value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonType {
+    /// JSON `null`
+    Null,
+    /// JSON boolean
+    Bool,
+    /// Any JSON number
+    Number,
+    /// A JSON number without a fractional part
+    Integer,
+    /// JSON string
+    String,
+    /// JSON array
+    Array,
+    /// JSON object
+    Object,
+}
+
+impl JsonType {
+    /// Check if a value has this type
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            JsonType::Null => value.is_null(),
+            JsonType::Bool => value.is_boolean(),
+            JsonType::Number => value.is_number(),
+            JsonType::Integer => value.is_i64() || value.is_u64(),
+            JsonType::String => value.is_string(),
+            JsonType::Array => value.is_array(),
+            JsonType::Object => value.is_object(),
+        }
+    }
+    
+    /// Get the type of a value
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonType::Null,
+            Value::Bool(_) => JsonType::Bool,
+            Value::Number(n) if n.is_i64() || n.is_u64() => JsonType::Integer,
+            Value::Number(_) => JsonType::Number,
+            Value::String(_) => JsonType::String,
+            Value::Array(_) => JsonType::Array,
+            Value::Object(_) => JsonType::Object,
+        }
+    }
+}
+
+impl std::fmt::Display for JsonType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonType::Null => write!(f, "null"),
+            JsonType::Bool => write!(f, "bool"),
+            JsonType::Number => write!(f, "number"),
+            JsonType::Integer => write!(f, "integer"),
+            JsonType::String => write!(f, "string"),
+            JsonType::Array => write!(f, "array"),
+            JsonType::Object => write!(f, "object"),
+        }
+    }
+}
+
+/// Declares the expected types of context fields
+///
+/// Only fields listed in the schema are checked; other fields, and listed
+/// fields that are absent, are allowed.
+#[derive(Debug, Clone, Default)]
+pub struct ContextSchema {
+    fields: HashMap<String, JsonType>,
+}
+
+impl ContextSchema {
+    /// Create an empty schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Declare the expected type of a context field
+    pub fn field(mut self, key: impl Into<String>, expected: JsonType) -> Self {
+        self.fields.insert(key.into(), expected);
+        self
+    }
+    
+    /// Check the entry's context against the schema
+    pub fn validate(&self, entry: &LogEntry) -> Result<()> {
+        let mut violations: Vec<String> = self.fields
+            .iter()
+            .filter_map(|(key, expected)| {
+                let value = entry.context.get(key)?;
+                if expected.matches(value) {
+                    None
+                } else {
+                    Some(format!(
+                        "context field '{}' must be {}, found {}",
+                        key, expected, JsonType::of(value)
+                    ))
+                }
+            })
+            .collect();
+        
+        if violations.is_empty() {
+            return Ok(());
+        }
+        violations.sort();
+        Err(Error::LoggingError(format!("Schema violation: {}", violations.join("; "))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    
+    fn schema() -> ContextSchema {
+        ContextSchema::new()
+            .field("user_id", JsonType::String)
+            .field("status", JsonType::Integer)
+    }
+    
+    #[test]
+    fn test_conforming_entry() {
+        let mut entry = LogEntry::new("Request handled", LogLevel::Info);
+        entry.add_context("user_id", "12345").unwrap();
+        entry.add_context("status", 200).unwrap();
+        entry.add_context("unlisted", vec![1, 2]).unwrap();
+        
+        assert!(schema().validate(&entry).is_ok());
+    }
+    
+    #[test]
+    fn test_type_mismatch() {
+        let mut entry = LogEntry::new("Request handled", LogLevel::Info);
+        entry.add_context("user_id", "12345").unwrap();
+        entry.add_context("status", "200").unwrap();
+        
+        match schema().validate(&entry) {
+            Err(Error::LoggingError(msg)) => {
+                assert!(msg.contains("'status' must be integer, found string"));
+                assert!(!msg.contains("user_id"));
+            },
+            other => panic!("expected schema violation, got {:?}", other),
+        }
+    }
+}
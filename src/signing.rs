@@ -0,0 +1,134 @@
+//! Tamper-evident log entries using HMAC-SHA256 signatures
+//!
+//! The signature covers the entry's canonical JSON (without the signature
+//! itself) and is stored in the `signature` metadata field.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use serde_json::Value;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Metadata field holding the hex-encoded signature
+pub const SIGNATURE_FIELD: &str = "signature";
+
+/// Signs and verifies log entries with a shared secret key
+pub struct Signer {
+    key: Vec<u8>,
+}
+
+impl Signer {
+    /// Create a signer using the given secret key
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+    
+    /// Compute the signature of an entry and attach it to its metadata
+    ///
+    /// Any existing signature is replaced. Fails if the entry's metadata is
+    /// omitted, since the signature would be dropped from the output.
+    pub fn sign(&self, entry: &mut LogEntry) -> Result<()> {
+        if entry.metadata.is_omitted() {
+            return Err(Error::LoggingError(
+                "Cannot sign an entry without metadata".to_string()
+            ));
+        }
+        entry.metadata.custom.remove(SIGNATURE_FIELD);
+        let tag = self.mac_for(entry)?.finalize().into_bytes();
+        entry.metadata.custom.insert(SIGNATURE_FIELD.to_string(), Value::String(to_hex(&tag)));
+        Ok(())
+    }
+    
+    /// Check that the entry carries a valid signature
+    pub fn verify(&self, entry: &LogEntry) -> bool {
+        let signature = match entry.metadata.custom.get(SIGNATURE_FIELD) {
+            Some(Value::String(s)) => s,
+            _ => return false,
+        };
+        let Some(expected) = from_hex(signature) else {
+            return false;
+        };
+        
+        let mut unsigned = entry.clone();
+        unsigned.metadata.custom.remove(SIGNATURE_FIELD);
+        match self.mac_for(&unsigned) {
+            Ok(mac) => mac.verify_slice(&expected).is_ok(),
+            Err(_) => false,
+        }
+    }
+    
+    fn mac_for(&self, entry: &LogEntry) -> Result<HmacSha256> {
+        let mut mac = HmacSha256::new_from_slice(&self.key)
+            .map_err(|e| Error::LoggingError(format!("Invalid signing key: {}", e)))?;
+        mac.update(entry.to_canonical_json()?.as_bytes());
+        Ok(mac)
+    }
+}
+
+impl std::fmt::Debug for Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the secret key
+        f.debug_struct("Signer").finish_non_exhaustive()
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = Signer::new("top-secret");
+        let mut entry = LogEntry::new("Funds transferred", LogLevel::Info);
+        entry.add_context("amount", 250).unwrap();
+        
+        signer.sign(&mut entry).unwrap();
+        assert!(entry.metadata.custom.contains_key(SIGNATURE_FIELD));
+        assert!(signer.verify(&entry));
+        
+        // Round-tripping through JSON keeps the signature valid
+        let parsed: LogEntry = serde_json::from_str(&entry.to_json().unwrap()).unwrap();
+        assert!(signer.verify(&parsed));
+        
+        assert!(!Signer::new("other-key").verify(&entry));
+    }
+    
+    #[test]
+    fn test_verify_detects_tampering() {
+        let signer = Signer::new("top-secret");
+        let mut entry = LogEntry::new("Funds transferred", LogLevel::Info);
+        signer.sign(&mut entry).unwrap();
+        
+        entry.message = "Funds not transferred".to_string();
+        assert!(!signer.verify(&entry));
+        
+        let unsigned = LogEntry::new("Never signed", LogLevel::Info);
+        assert!(!signer.verify(&unsigned));
+    }
+    
+    #[test]
+    fn test_sign_rejects_omitted_metadata() {
+        let signer = Signer::new("top-secret");
+        let mut entry = LogEntry::lightweight("Funds transferred", LogLevel::Info);
+        assert!(signer.sign(&mut entry).is_err());
+        assert!(entry.metadata.custom.is_empty());
+    }
+}
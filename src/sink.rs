@@ -0,0 +1,472 @@
+//! Output destinations for log entries
+
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::core::LogEntry;
+use crate::error::{Result, Error};
+use crate::formatter::{Formatter, SimpleFormatter};
+
+/// Destination that log entries are written to
+pub trait Sink: Send + Sync {
+    /// Write a single entry
+    fn emit(&self, entry: &LogEntry) -> Result<()>;
+    
+    /// Flush any buffered output
+    fn flush(&self) -> Result<()>;
+}
+
+/// Sink writing formatted entries to an `io::Write`, one per line
+///
+/// With the default `SimpleFormatter` the output is NDJSON.
+pub struct WriterSink<W, F = SimpleFormatter> {
+    writer: Mutex<W>,
+    formatter: F,
+}
+
+impl<W: Write> WriterSink<W> {
+    /// Create a sink writing NDJSON to `writer`
+    pub fn new(writer: W) -> Self {
+        Self::with_formatter(writer, SimpleFormatter::new())
+    }
+}
+
+impl<W: Write, F: Formatter> WriterSink<W, F> {
+    /// Create a sink writing entries formatted by `formatter`
+    pub fn with_formatter(writer: W, formatter: F) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            formatter,
+        }
+    }
+    
+    /// Consume the sink, returning the underlying writer
+    pub fn into_inner(self) -> Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|_| Error::LoggingError("Writer sink lock poisoned".to_string()))
+    }
+    
+    fn lock_writer(&self) -> Result<std::sync::MutexGuard<'_, W>> {
+        self.writer
+            .lock()
+            .map_err(|_| Error::LoggingError("Writer sink lock poisoned".to_string()))
+    }
+}
+
+impl<W, F> Sink for WriterSink<W, F>
+where
+    W: Write + Send,
+    F: Formatter + Send + Sync,
+{
+    fn emit(&self, entry: &LogEntry) -> Result<()> {
+        let line = self.formatter.format(entry)?;
+        let mut writer = self.lock_writer()?;
+        writeln!(writer, "{}", line)?;
+        Ok(())
+    }
+    
+    fn flush(&self) -> Result<()> {
+        self.lock_writer()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Sink that discards every entry
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullSink;
+
+impl NullSink {
+    /// Create a new null sink
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Sink for NullSink {
+    fn emit(&self, _entry: &LogEntry) -> Result<()> {
+        Ok(())
+    }
+    
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Sink that records emitted entries in memory
+///
+/// Intended as a test double for code that emits logs: entries are kept
+/// in emission order and can be inspected with `entries`.
+#[derive(Debug, Default)]
+pub struct CaptureSink {
+    entries: Mutex<Vec<LogEntry>>,
+}
+
+impl CaptureSink {
+    /// Create an empty capture sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Get a copy of the captured entries, in emission order
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.lock_entries().clone()
+    }
+    
+    /// Number of captured entries
+    pub fn len(&self) -> usize {
+        self.lock_entries().len()
+    }
+    
+    /// Check if nothing has been captured
+    pub fn is_empty(&self) -> bool {
+        self.lock_entries().is_empty()
+    }
+    
+    /// Discard all captured entries
+    pub fn clear(&self) {
+        self.lock_entries().clear();
+    }
+    
+    /// Lock the entries, recovering them if a panicking test poisoned the lock
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, Vec<LogEntry>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Sink for CaptureSink {
+    fn emit(&self, entry: &LogEntry) -> Result<()> {
+        self.lock_entries().push(entry.clone());
+        Ok(())
+    }
+    
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Sink that writes every entry to several destinations
+///
+/// A failing destination doesn't stop the others: every sink is called, and
+/// if any failed their errors are combined into one `Error::LoggingError`.
+#[derive(Default)]
+pub struct MultiSink {
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl MultiSink {
+    /// Create a fan-out sink without destinations
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add a destination
+    pub fn with_sink<S: Sink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+    
+    /// Number of destinations
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+    
+    /// Check if there are no destinations
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+    
+    /// Run `op` on every sink, combining the errors of those that fail
+    fn for_each(&self, action: &str, op: impl Fn(&dyn Sink) -> Result<()>) -> Result<()> {
+        let errors: Vec<String> = self.sinks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, sink)| op(sink.as_ref()).err().map(|e| format!("sink {}: {}", i, e)))
+            .collect();
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::LoggingError(format!(
+                "{} of {} sinks failed to {}: {}",
+                errors.len(), self.sinks.len(), action, errors.join("; ")
+            )))
+        }
+    }
+}
+
+impl Sink for MultiSink {
+    fn emit(&self, entry: &LogEntry) -> Result<()> {
+        self.for_each("emit", |sink| sink.emit(entry))
+    }
+    
+    fn flush(&self) -> Result<()> {
+        self.for_each("flush", |sink| sink.flush())
+    }
+}
+
+/// Sink that streams entries as NDJSON over a TCP connection
+///
+/// The connection is opened lazily on the first write. When a write fails
+/// the sink reconnects with exponential backoff; if every attempt fails the
+/// last I/O error is returned as `Error::IoError`.
+pub struct TcpSink {
+    addr: SocketAddr,
+    stream: Mutex<Option<TcpStream>>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    connect_timeout: Duration,
+}
+
+impl TcpSink {
+    /// Create a sink that sends entries to the collector at `addr`
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            stream: Mutex::new(None),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            connect_timeout: Duration::from_secs(5),
+        }
+    }
+    
+    /// Set how often a failed write is retried and the first retry delay
+    ///
+    /// The delay doubles after each failed attempt.
+    pub fn with_retry(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+    
+    /// Set the timeout for establishing a connection
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+    
+    /// Get the collector address
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+    
+    fn lock_stream(&self) -> Result<std::sync::MutexGuard<'_, Option<TcpStream>>> {
+        self.stream
+            .lock()
+            .map_err(|_| Error::LoggingError("TCP sink lock poisoned".to_string()))
+    }
+    
+    fn write_line(&self, stream: &mut Option<TcpStream>, line: &[u8]) -> std::io::Result<()> {
+        if stream.is_none() {
+            let conn = TcpStream::connect_timeout(&self.addr, self.connect_timeout)?;
+            conn.set_nodelay(true)?;
+            *stream = Some(conn);
+        }
+        
+        match stream.as_mut() {
+            Some(conn) => conn.write_all(line),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Sink for TcpSink {
+    /// Write an entry as a single NDJSON line
+    fn emit(&self, entry: &LogEntry) -> Result<()> {
+        let mut line = entry.to_json()?;
+        line.push('\n');
+        
+        let mut stream = self.lock_stream()?;
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+        
+        loop {
+            match self.write_line(&mut stream, line.as_bytes()) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    // Drop the broken connection so the next attempt reconnects
+                    *stream = None;
+                    if attempt >= self.max_retries {
+                        return Err(Error::IoError(e));
+                    }
+                    attempt += 1;
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                },
+            }
+        }
+    }
+    
+    /// Flush buffered data on the current connection, if any
+    fn flush(&self) -> Result<()> {
+        let mut stream = self.lock_stream()?;
+        if let Some(conn) = stream.as_mut() {
+            conn.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use crate::core::LogLevel;
+    
+    #[test]
+    fn test_tcp_sink_sends_ndjson() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        let receiver = thread::spawn(move || {
+            let (conn, _) = listener.accept().unwrap();
+            BufReader::new(conn)
+                .lines()
+                .take(2)
+                .map(|line| serde_json::from_str::<LogEntry>(&line.unwrap()).unwrap())
+                .collect::<Vec<_>>()
+        });
+        
+        let sink = TcpSink::new(addr);
+        let mut entry = LogEntry::new("first", LogLevel::Info);
+        entry.add_context("n", 1).unwrap();
+        sink.emit(&entry).unwrap();
+        sink.emit(&LogEntry::new("second", LogLevel::Error)).unwrap();
+        sink.flush().unwrap();
+        
+        let received = receiver.join().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received[0].semantically_eq(&entry));
+        assert_eq!(received[1].message, "second");
+        assert_eq!(received[1].level, LogLevel::Error);
+    }
+    
+    #[test]
+    fn test_writer_sink_writes_lines() {
+        let sink = WriterSink::new(Vec::new());
+        let first = LogEntry::new("first", LogLevel::Info);
+        let second = LogEntry::new("second", LogLevel::Warn);
+        sink.emit(&first).unwrap();
+        sink.emit(&second).unwrap();
+        sink.flush().unwrap();
+        
+        let output = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        assert_eq!(output, format!("{}\n{}\n", first.to_json().unwrap(), second.to_json().unwrap()));
+    }
+    
+    #[test]
+    fn test_null_sink_is_noop() {
+        let sink = NullSink::new();
+        assert!(sink.emit(&LogEntry::new("dropped", LogLevel::Fatal)).is_ok());
+        assert!(sink.flush().is_ok());
+    }
+    
+    #[test]
+    fn test_capture_sink_records_entries() {
+        let sink = CaptureSink::new();
+        let emitted: Vec<LogEntry> = ["one", "two", "three"]
+            .iter()
+            .map(|msg| LogEntry::new(*msg, LogLevel::Info))
+            .collect();
+        for entry in &emitted {
+            sink.emit(entry).unwrap();
+        }
+        
+        let captured = sink.entries();
+        assert_eq!(captured.len(), 3);
+        for (captured, emitted) in captured.iter().zip(&emitted) {
+            assert!(captured.semantically_eq(emitted));
+            assert_eq!(captured.metadata.id, emitted.metadata.id);
+        }
+        
+        sink.clear();
+        assert!(sink.is_empty());
+    }
+    
+    #[test]
+    fn test_capture_sink_concurrent_emits() {
+        let sink = CaptureSink::new();
+        thread::scope(|scope| {
+            for t in 0..4 {
+                let sink = &sink;
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        sink.emit(&LogEntry::new(format!("{}-{}", t, i), LogLevel::Debug)).unwrap();
+                    }
+                });
+            }
+        });
+        
+        let captured = sink.entries();
+        assert_eq!(captured.len(), 200);
+        // Each thread's entries keep their relative order
+        for t in 0..4 {
+            let prefix = format!("{}-", t);
+            let ours: Vec<String> = captured
+                .iter()
+                .filter_map(|e| e.message.strip_prefix(&prefix).map(str::to_string))
+                .collect();
+            let expected: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+            assert_eq!(ours, expected);
+        }
+    }
+    
+    #[test]
+    fn test_multi_sink_continues_on_error() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        /// Records messages, failing every other emit when flaky
+        #[derive(Clone, Default)]
+        struct Recorder {
+            received: Arc<Mutex<Vec<String>>>,
+            calls: Arc<AtomicUsize>,
+            flaky: bool,
+        }
+        
+        impl Sink for Recorder {
+            fn emit(&self, entry: &LogEntry) -> Result<()> {
+                let call = self.calls.fetch_add(1, Ordering::SeqCst);
+                if self.flaky && call % 2 == 1 {
+                    return Err(Error::LoggingError("collector unavailable".to_string()));
+                }
+                self.received.lock().unwrap().push(entry.message.clone());
+                Ok(())
+            }
+            
+            fn flush(&self) -> Result<()> {
+                Ok(())
+            }
+        }
+        
+        let local = Recorder::default();
+        let remote = Recorder { flaky: true, ..Default::default() };
+        let sink = MultiSink::new().with_sink(remote.clone()).with_sink(local.clone());
+        assert_eq!(sink.len(), 2);
+        
+        let results: Vec<_> = (0..4)
+            .map(|i| sink.emit(&LogEntry::new(format!("entry {}", i), LogLevel::Info)))
+            .collect();
+        assert!(results[0].is_ok() && results[2].is_ok());
+        assert!(matches!(&results[1], Err(Error::LoggingError(msg)) if msg.contains("1 of 2 sinks")));
+        assert!(results[3].is_err());
+        
+        assert_eq!(*local.received.lock().unwrap(), ["entry 0", "entry 1", "entry 2", "entry 3"]);
+        assert_eq!(*remote.received.lock().unwrap(), ["entry 0", "entry 2"]);
+        assert!(sink.flush().is_ok());
+    }
+    
+    #[test]
+    fn test_tcp_sink_reports_persistent_failure() {
+        // Reserve a port, then close it so nothing is listening
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        
+        let sink = TcpSink::new(addr).with_retry(2, Duration::from_millis(1));
+        let result = sink.emit(&LogEntry::new("lost", LogLevel::Info));
+        assert!(matches!(result, Err(Error::IoError(_))));
+    }
+}
@@ -0,0 +1,175 @@
+//! Lightweight statistics collected over log entries
+
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::core::{LogEntry, LogLevel};
+
+/// Histogram of serialized entry sizes for capacity planning
+///
+/// Each bucket counts entries whose JSON size is at most its upper bound
+/// (and above the previous bound). Sizes above the largest bound are
+/// counted in a final `usize::MAX` bucket. Recording is lock-free.
+#[derive(Debug)]
+pub struct SizeHistogram {
+    bounds: Vec<usize>,
+    counts: Vec<AtomicU64>,
+}
+
+impl Default for SizeHistogram {
+    fn default() -> Self {
+        Self::new(vec![256, 512, 1024, 4096, 16384, 65536])
+    }
+}
+
+impl SizeHistogram {
+    /// Create a histogram with the given bucket upper bounds in bytes
+    pub fn new(mut bounds: Vec<usize>) -> Self {
+        bounds.sort_unstable();
+        bounds.dedup();
+        if bounds.last() != Some(&usize::MAX) {
+            bounds.push(usize::MAX);
+        }
+        
+        let counts = bounds.iter().map(|_| AtomicU64::new(0)).collect();
+        Self { bounds, counts }
+    }
+    
+    /// Record the serialized size of an entry
+    pub fn record(&self, entry: &LogEntry) {
+        self.record_size(entry.estimated_json_size());
+    }
+    
+    /// Record a size in bytes
+    pub fn record_size(&self, size: usize) {
+        let index = self.bounds.partition_point(|&bound| bound < size);
+        self.counts[index].fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// Get `(upper_bound, count)` pairs for every bucket
+    pub fn buckets(&self) -> Vec<(usize, u64)> {
+        self.bounds
+            .iter()
+            .zip(&self.counts)
+            .map(|(bound, count)| (*bound, count.load(Ordering::Relaxed)))
+            .collect()
+    }
+    
+    /// Total number of recorded entries
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|count| count.load(Ordering::Relaxed)).sum()
+    }
+    
+    /// Reset all buckets to zero
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Tracks the most severe level seen, e.g. to pick a CLI exit code
+///
+/// Recording is a single lock-free atomic update.
+#[derive(Debug, Default)]
+pub struct SeverityTracker {
+    /// One more than the index of the worst level in `LogLevel::ALL`; 0 if none
+    worst: AtomicU8,
+}
+
+impl SeverityTracker {
+    /// Create a tracker that has seen no entries
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record an entry's level
+    pub fn record(&self, entry: &LogEntry) {
+        self.record_level(entry.level);
+    }
+    
+    /// Record a level
+    pub fn record_level(&self, level: LogLevel) {
+        let rank = LogLevel::ALL.iter().position(|l| *l == level).unwrap_or(0) as u8 + 1;
+        self.worst.fetch_max(rank, Ordering::Relaxed);
+    }
+    
+    /// Most severe level recorded so far, if any
+    pub fn worst(&self) -> Option<LogLevel> {
+        match self.worst.load(Ordering::Relaxed) {
+            0 => None,
+            rank => LogLevel::ALL.get(rank as usize - 1).copied(),
+        }
+    }
+    
+    /// Exit code reflecting the worst level seen
+    ///
+    /// `Fatal` maps to 2, `Error` and `Critical` to 1, anything else to 0.
+    pub fn exit_code(&self) -> i32 {
+        match self.worst() {
+            Some(LogLevel::Fatal) => 2,
+            Some(LogLevel::Error | LogLevel::Critical) => 1,
+            _ => 0,
+        }
+    }
+    
+    /// Forget all recorded levels
+    pub fn reset(&self) {
+        self.worst.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LogLevel;
+    
+    fn entry_with_payload(len: usize) -> LogEntry {
+        let mut entry = LogEntry::new("sized", LogLevel::Info);
+        entry.add_context("payload", "x".repeat(len)).unwrap();
+        entry
+    }
+    
+    #[test]
+    fn test_size_histogram_buckets() {
+        let histogram = SizeHistogram::new(vec![1024, 256]);
+        
+        histogram.record(&entry_with_payload(0));
+        histogram.record(&entry_with_payload(10));
+        histogram.record(&entry_with_payload(500));
+        histogram.record(&entry_with_payload(5000));
+        histogram.record_size(256);
+        
+        assert_eq!(
+            histogram.buckets(),
+            vec![(256, 3), (1024, 1), (usize::MAX, 1)]
+        );
+        assert_eq!(histogram.total(), 5);
+        
+        histogram.reset();
+        assert_eq!(histogram.total(), 0);
+    }
+    
+    #[test]
+    fn test_severity_tracker_exit_code() {
+        let tracker = SeverityTracker::new();
+        assert_eq!(tracker.worst(), None);
+        assert_eq!(tracker.exit_code(), 0);
+        
+        for level in [LogLevel::Info, LogLevel::Warn, LogLevel::Debug] {
+            tracker.record(&LogEntry::new("ok", level));
+        }
+        assert_eq!(tracker.worst(), Some(LogLevel::Warn));
+        assert_eq!(tracker.exit_code(), 0);
+        
+        tracker.record(&LogEntry::new("failed", LogLevel::Error));
+        tracker.record(&LogEntry::new("recovered", LogLevel::Info));
+        assert_eq!(tracker.exit_code(), 1);
+        
+        tracker.record_level(LogLevel::Fatal);
+        assert_eq!(tracker.worst(), Some(LogLevel::Fatal));
+        assert_eq!(tracker.exit_code(), 2);
+        
+        tracker.reset();
+        assert_eq!(tracker.exit_code(), 0);
+    }
+}
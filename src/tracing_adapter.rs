@@ -0,0 +1,200 @@
+//! Adapter for events from the `tracing` crate
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level};
+
+use crate::adapter::{Adapter, AdapterOptions};
+use crate::core::{LogEntry, LogLevel};
+use crate::error::Result;
+
+/// Name of the field holding a tracing event's message
+const MESSAGE_FIELD: &str = "message";
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::TRACE => LogLevel::Trace,
+            Level::DEBUG => LogLevel::Debug,
+            Level::INFO => LogLevel::Info,
+            Level::WARN => LogLevel::Warn,
+            Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/// Field visitor that records tracing fields on a log entry
+///
+/// The `message` field becomes the entry's message; every other field is
+/// added as context, keeping integers, floats and booleans typed.
+pub struct ContextVisitor<'a> {
+    entry: &'a mut LogEntry,
+}
+
+impl<'a> ContextVisitor<'a> {
+    /// Create a visitor recording into `entry`
+    pub fn new(entry: &'a mut LogEntry) -> Self {
+        Self { entry }
+    }
+    
+    fn record_value(&mut self, field: &Field, value: serde_json::Value) {
+        self.entry.context.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for ContextVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let text = format!("{:?}", value);
+        if field.name() == MESSAGE_FIELD {
+            self.entry.message = text;
+        } else {
+            self.record_value(field, text.into());
+        }
+    }
+    
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == MESSAGE_FIELD {
+            self.entry.message = value.to_string();
+        } else {
+            self.record_value(field, value.into());
+        }
+    }
+    
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_value(field, value.into());
+    }
+    
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record_value(field, value.into());
+    }
+    
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_value(field, value.into());
+    }
+    
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record_value(field, value.into());
+    }
+    
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_value(field, value.to_string().into());
+    }
+}
+
+/// Adapter converting `tracing` events into log entries
+///
+/// The event's target is stored in the `target` metadata field, and its
+/// file and line become the entry's source when `include_source` is set.
+pub struct TracingAdapter {
+    options: AdapterOptions,
+}
+
+impl TracingAdapter {
+    /// Create a new tracing adapter
+    pub fn new() -> Self {
+        Self {
+            options: AdapterOptions::default(),
+        }
+    }
+    
+    /// Create with specific options
+    pub fn with_options(options: AdapterOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Default for TracingAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Adapter<Event<'_>> for TracingAdapter {
+    fn convert(&self, event: &Event<'_>) -> Result<LogEntry> {
+        let meta = event.metadata();
+        let mut entry = LogEntry::new(String::new(), LogLevel::from(*meta.level()));
+        event.record(&mut ContextVisitor::new(&mut entry));
+        
+        entry.metadata.add_field("target", meta.target())?;
+        if self.options.include_source {
+            entry.metadata.source = meta.file().map(str::to_string);
+            entry.metadata.line = meta.line();
+        }
+        self.options.apply_module_source(&mut entry, meta.module_path());
+        
+        self.options.default_context.apply(&mut entry);
+        self.options.clamp_timestamp(&mut entry)?;
+        Ok(entry)
+    }
+    
+    fn configure(&mut self, options: AdapterOptions) {
+        self.options = options;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Metadata, Subscriber};
+    
+    /// Subscriber converting every event with a `TracingAdapter`
+    struct Collector {
+        adapter: TracingAdapter,
+        entries: Arc<Mutex<Vec<LogEntry>>>,
+    }
+    
+    impl Subscriber for Collector {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+        
+        fn new_span(&self, _span: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+        
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        
+        fn event(&self, event: &Event<'_>) {
+            let entry = self.adapter.convert(event).unwrap();
+            self.entries.lock().unwrap().push(entry);
+        }
+        
+        fn enter(&self, _span: &Id) {}
+        
+        fn exit(&self, _span: &Id) {}
+    }
+    
+    #[test]
+    fn test_convert_tracing_event() {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let collector = Collector {
+            adapter: TracingAdapter::new(),
+            entries: Arc::clone(&entries),
+        };
+        
+        tracing::subscriber::with_default(collector, || {
+            tracing::warn!(
+                target: "api",
+                status_code = 429,
+                retry = true,
+                user = "ada",
+                "Rate limit exceeded"
+            );
+        });
+        
+        let entries = entries.lock().unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.message, "Rate limit exceeded");
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.context["status_code"], 429);
+        assert_eq!(entry.context["retry"], true);
+        assert_eq!(entry.context["user"], "ada");
+        assert!(!entry.context.contains_key("message"));
+        assert_eq!(entry.metadata.custom["target"], "api");
+        assert!(entry.metadata.source.as_deref().is_some_and(|s| s.ends_with("tracing_adapter.rs")));
+    }
+}
@@ -0,0 +1,157 @@
+//! `tracing-subscriber` layer writing events as formatted log entries
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::adapter::Adapter;
+use crate::core::{LogEntry, LogLevel};
+use crate::formatter::{Formatter, SimpleFormatter};
+use crate::tracing_adapter::{ContextVisitor, TracingAdapter};
+
+/// Fields recorded on a span, stored in the span's extensions
+struct SpanFields(HashMap<String, Value>);
+
+/// Record a span's field values into a context map
+fn record_fields(values: &Record<'_>, fields: &mut HashMap<String, Value>) {
+    let mut scratch = LogEntry::lightweight(String::new(), LogLevel::Trace);
+    values.record(&mut ContextVisitor::new(&mut scratch));
+    fields.extend(scratch.context);
+}
+
+/// Layer turning every `tracing` event into a `LogEntry` and writing it
+///
+/// Events are converted with a `TracingAdapter`. Fields of the spans the
+/// event is inside are added to its context, innermost span first; the
+/// event's own fields take precedence. Each entry is formatted with `F`
+/// and written to `W` as one line, so the default `SimpleFormatter`
+/// produces NDJSON. Formatting and write errors are ignored.
+pub struct ChrysalisLayer<W, F = SimpleFormatter> {
+    writer: Mutex<W>,
+    formatter: F,
+    adapter: TracingAdapter,
+}
+
+impl<W: Write> ChrysalisLayer<W> {
+    /// Create a layer writing NDJSON to `writer`
+    pub fn new(writer: W) -> Self {
+        Self::with_formatter(writer, SimpleFormatter::new())
+    }
+}
+
+impl<W: Write, F: Formatter> ChrysalisLayer<W, F> {
+    /// Create a layer writing entries formatted by `formatter`
+    pub fn with_formatter(writer: W, formatter: F) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            formatter,
+            adapter: TracingAdapter::new(),
+        }
+    }
+    
+    /// Use a specifically configured adapter for event conversion
+    pub fn with_adapter(mut self, adapter: TracingAdapter) -> Self {
+        self.adapter = adapter;
+        self
+    }
+}
+
+impl<S, W, F> Layer<S> for ChrysalisLayer<W, F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: Write + Send + 'static,
+    F: Formatter + Send + Sync + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        
+        let mut fields = HashMap::new();
+        record_fields(&Record::new(attrs.values()), &mut fields);
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+    
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        
+        let mut extensions = span.extensions_mut();
+        if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+            record_fields(values, fields);
+        }
+    }
+    
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Ok(mut entry) = self.adapter.convert(event) else {
+            return;
+        };
+        
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    for (key, value) in fields {
+                        entry.context.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+            }
+        }
+        
+        let Ok(line) = self.formatter.format(&entry) else {
+            return;
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+    
+    /// In-memory writer whose contents can be read after the subscriber ends
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+    
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[test]
+    fn test_layer_writes_ndjson() {
+        let buffer = SharedBuffer::default();
+        let subscriber = tracing_subscriber::registry().with(ChrysalisLayer::new(buffer.clone()));
+        
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("request", request_id = "r-42", attempt = 1);
+            let _guard = request.enter();
+            tracing::info!(attempt = 2, "Handled request");
+        });
+        
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        
+        let value: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(value["message"], "Handled request");
+        assert_eq!(value["level"], "info");
+        assert_eq!(value["context"]["request_id"], "r-42");
+        assert_eq!(value["context"]["attempt"], 2);
+    }
+}
@@ -7,19 +7,30 @@
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::path::Path;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use chrono::{DateTime, Utc, SecondsFormat};
 use rand::rng;
 use uuid::Uuid;
 use serde_json::Value;
 
 use crate::error::{Result, Error};
-use crate::core::LogLevel;
+use crate::core::{LogEntry, LogLevel};
 
 /// Format a timestamp to ISO 8601 format with millisecond precision
 pub fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
     timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
 
+/// Format a timestamp in the given timezone, with millisecond precision
+///
+/// The result is RFC 3339 with the zone's UTC offset, e.g.
+/// `2024-01-15T07:30:00.000-05:00`. Only the rendering changes; the
+/// instant is the same.
+#[cfg(feature = "timezones")]
+pub fn format_timestamp_tz(timestamp: &DateTime<Utc>, tz: chrono_tz::Tz) -> String {
+    timestamp.with_timezone(&tz).to_rfc3339_opts(SecondsFormat::Millis, false)
+}
+
 /// Format a timestamp to a custom format
 pub fn format_timestamp_custom(timestamp: &DateTime<Utc>, format: &str) -> String {
     timestamp.format(format).to_string()
@@ -52,14 +63,14 @@ pub fn current_timestamp_millis() -> i64 {
 
 /// Convert a Unix timestamp to DateTime<Utc>
 pub fn timestamp_to_datetime(timestamp: i64) -> DateTime<Utc> {
-    DateTime::from_timestamp(timestamp, 0).unwrap_or_else(|| Utc::now())
+    DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now)
 }
 
 /// Convert milliseconds since epoch to DateTime<Utc>
 pub fn millis_to_datetime(millis: i64) -> DateTime<Utc> {
     let secs = millis / 1000;
     let nanos = ((millis % 1000) * 1_000_000) as u32;
-    DateTime::from_timestamp(secs, nanos).unwrap_or_else(|| Utc::now())
+    DateTime::from_timestamp(secs, nanos).unwrap_or_else(Utc::now)
 }
 
 /// Sanitize a field name for safe JSON use
@@ -81,11 +92,22 @@ pub fn nested_field_path(parent: &str, child: &str) -> String {
 ///
 /// Adds an ellipsis to indicate truncation if needed.
 pub fn truncate_string(s: &str, max_length: usize) -> String {
-    if s.len() <= max_length {
-        s.to_string()
-    } else {
-        format!("{}...", &s[0..max_length.saturating_sub(3)])
+    truncate_string_with(s, max_length, "...")
+}
+
+/// Truncate a string to at most `max_length` characters using a custom marker
+///
+/// The marker counts toward the length budget, so the result never exceeds
+/// `max_length` characters (unless the marker itself is longer).
+pub fn truncate_string_with(s: &str, max_length: usize, marker: &str) -> String {
+    if s.chars().count() <= max_length {
+        return s.to_string();
     }
+    
+    let keep = max_length.saturating_sub(marker.chars().count());
+    let mut truncated: String = s.chars().take(keep).collect();
+    truncated.push_str(marker);
+    truncated
 }
 
 /// Get the filename from a path
@@ -163,6 +185,35 @@ pub fn format_duration(duration_ms: i64) -> String {
     }
 }
 
+/// Format a duration in milliseconds as an ISO 8601 duration
+///
+/// Uses hours, minutes and (fractional) seconds only, e.g. `PT1M5S` or
+/// `PT0.25S`, since days are ambiguous across DST changes. Zero is `PT0S`
+/// and negative durations get a leading `-`.
+pub fn format_iso_duration(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "" };
+    let ms = ms.unsigned_abs();
+    let hours = ms / 3_600_000;
+    let minutes = ms / 60_000 % 60;
+    let seconds = ms / 1000 % 60;
+    let millis = ms % 1000;
+    
+    let mut out = format!("{}PT", sign);
+    if hours > 0 {
+        out.push_str(&format!("{}H", hours));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{}M", minutes));
+    }
+    if millis > 0 {
+        let fraction = format!("{:03}", millis);
+        out.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+    } else if seconds > 0 || (hours == 0 && minutes == 0) {
+        out.push_str(&format!("{}S", seconds));
+    }
+    out
+}
+
 /// Safely get a value from a serde_json::Value by path
 ///
 /// The path is a dot-separated string of field names.
@@ -196,10 +247,30 @@ pub fn get_nested_value<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
     Some(current)
 }
 
+/// How `merge_json_values_with` combines two arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMergeMode {
+    /// The update's array replaces the base array
+    #[default]
+    Replace,
+    /// The update's elements are appended to the base array
+    Concat,
+    /// Like `Concat`, skipping elements already present in the result
+    ConcatDedup,
+}
+
 /// Create a deep-merged version of two JSON values
 ///
 /// If there are conflicts, values from 'update' overwrite values from 'base'.
 pub fn merge_json_values(base: &Value, update: &Value) -> Value {
+    merge_json_values_with(base, update, ArrayMergeMode::Replace)
+}
+
+/// Deep-merge two JSON values, combining arrays according to `array_mode`
+///
+/// Objects merge key by key as in `merge_json_values`; any other
+/// conflicting values are taken from `update`.
+pub fn merge_json_values_with(base: &Value, update: &Value, array_mode: ArrayMergeMode) -> Value {
     match (base, update) {
         (Value::Object(base_map), Value::Object(update_map)) => {
             let mut result = base_map.clone();
@@ -207,7 +278,7 @@ pub fn merge_json_values(base: &Value, update: &Value) -> Value {
             for (k, v) in update_map {
                 match result.get(k) {
                     Some(base_value) => {
-                        result.insert(k.clone(), merge_json_values(base_value, v));
+                        result.insert(k.clone(), merge_json_values_with(base_value, v, array_mode));
                     },
                     None => {
                         result.insert(k.clone(), v.clone());
@@ -217,14 +288,122 @@ pub fn merge_json_values(base: &Value, update: &Value) -> Value {
             
             Value::Object(result)
         },
+        (Value::Array(base_arr), Value::Array(update_arr)) if array_mode != ArrayMergeMode::Replace => {
+            let mut result = base_arr.clone();
+            for item in update_arr {
+                if array_mode == ArrayMergeMode::ConcatDedup && result.contains(item) {
+                    continue;
+                }
+                result.push(item.clone());
+            }
+            Value::Array(result)
+        },
         (_, update_value) => update_value.clone(),
     }
 }
 
+/// Key listing the keys removed from an object in a `json_diff` result
+pub const DIFF_REMOVED_KEY: &str = "$removed";
+
+/// Compute the changes needed to turn `base` into `target`
+///
+/// Objects are compared key by key, recursively; the result holds only the
+/// keys whose values differ. Keys missing from `target` are listed under
+/// `DIFF_REMOVED_KEY`, so a removal is distinct from a value set to `null`.
+/// Any other differing values are replaced wholesale. Returns `None` if the
+/// values are equal. `apply_json_diff` turns `base` back into `target`.
+pub fn json_diff(base: &Value, target: &Value) -> Option<Value> {
+    if base == target {
+        return None;
+    }
+    
+    match (base, target) {
+        (Value::Object(base_map), Value::Object(target_map)) => {
+            let mut diff = serde_json::Map::new();
+            
+            for (k, v) in target_map {
+                match base_map.get(k) {
+                    Some(base_value) => {
+                        if let Some(changed) = json_diff(base_value, v) {
+                            diff.insert(k.clone(), changed);
+                        }
+                    },
+                    None => {
+                        diff.insert(k.clone(), v.clone());
+                    },
+                }
+            }
+            let removed: Vec<Value> = base_map
+                .keys()
+                .filter(|k| !target_map.contains_key(*k))
+                .map(|k| Value::String(k.clone()))
+                .collect();
+            if !removed.is_empty() {
+                diff.insert(DIFF_REMOVED_KEY.to_string(), Value::Array(removed));
+            }
+            
+            Some(Value::Object(diff))
+        },
+        (_, target_value) => Some(target_value.clone()),
+    }
+}
+
+/// Apply a `json_diff` result to `base`
+///
+/// Object diffs are applied key by key, recursively, dropping the keys
+/// listed under `DIFF_REMOVED_KEY`; any other diff value replaces the base
+/// value.
+pub fn apply_json_diff(base: &Value, diff: &Value) -> Value {
+    match (base, diff) {
+        (Value::Object(base_map), Value::Object(diff_map)) => {
+            let mut result = base_map.clone();
+            
+            for (k, v) in diff_map {
+                if k == DIFF_REMOVED_KEY {
+                    for removed in v.as_array().into_iter().flatten().filter_map(Value::as_str) {
+                        result.remove(removed);
+                    }
+                    continue;
+                }
+                
+                let applied = match result.get(k) {
+                    Some(base_value) => apply_json_diff(base_value, v),
+                    None => v.clone(),
+                };
+                result.insert(k.clone(), applied);
+            }
+            
+            Value::Object(result)
+        },
+        (_, diff_value) => diff_value.clone(),
+    }
+}
+
 /// Flatten a nested JSON object into a single-level map with dot notation for keys
 pub fn flatten_json(value: &Value, prefix: &str) -> HashMap<String, Value> {
+    flatten_json_depth(value, prefix, usize::MAX)
+}
+
+/// Flatten a nested JSON object, stopping after `max_depth` key levels
+///
+/// Keys are made of at most `max_depth` dot-separated segments (counting
+/// from `prefix`); deeper subtrees are kept as nested JSON values under
+/// the last flattened key. A `max_depth` of 0 is treated as 1.
+pub fn flatten_json_depth(value: &Value, prefix: &str, max_depth: usize) -> HashMap<String, Value> {
     let mut result = HashMap::new();
-    
+    let depth = if prefix.is_empty() { 0 } else { 1 };
+    flatten_depth_into(value, prefix, depth, max_depth.max(1), &mut result);
+    result
+}
+
+/// Recursive helper for `flatten_json_depth`
+fn flatten_depth_into(
+    value: &Value,
+    prefix: &str,
+    depth: usize,
+    max_depth: usize,
+    result: &mut HashMap<String, Value>,
+) {
     match value {
         Value::Object(map) => {
             for (k, v) in map {
@@ -234,18 +413,21 @@ pub fn flatten_json(value: &Value, prefix: &str) -> HashMap<String, Value> {
                     format!("{}.{}", prefix, k)
                 };
                 
+                if depth + 1 >= max_depth {
+                    result.insert(new_key, v.clone());
+                    continue;
+                }
+                
                 match v {
                     Value::Object(_) => {
-                        let nested = flatten_json(v, &new_key);
-                        result.extend(nested);
+                        flatten_depth_into(v, &new_key, depth + 1, max_depth, result);
                     },
                     Value::Array(arr) => {
                         for (i, item) in arr.iter().enumerate() {
                             let array_key = format!("{}[{}]", new_key, i);
                             match item {
                                 Value::Object(_) => {
-                                    let nested = flatten_json(item, &array_key);
-                                    result.extend(nested);
+                                    flatten_depth_into(item, &array_key, depth + 1, max_depth, result);
                                 },
                                 _ => {
                                     result.insert(array_key, item.clone());
@@ -267,10 +449,55 @@ pub fn flatten_json(value: &Value, prefix: &str) -> HashMap<String, Value> {
             }
         },
     }
-    
+}
+
+/// Flatten a nested JSON object like `flatten_json`, in a deterministic order
+///
+/// Object keys are visited in sorted order and array elements by index; an
+/// array's own key comes before its elements.
+pub fn flatten_json_ordered(value: &Value, prefix: &str) -> Vec<(String, Value)> {
+    let mut result = Vec::new();
+    flatten_ordered_into(value, prefix, &mut result);
     result
 }
 
+/// Recursive helper for `flatten_json_ordered`
+fn flatten_ordered_into(value: &Value, prefix: &str, result: &mut Vec<(String, Value)>) {
+    let Value::Object(map) = value else {
+        if !prefix.is_empty() {
+            result.push((prefix.to_string(), value.clone()));
+        }
+        return;
+    };
+    
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    
+    for key in keys {
+        let v = &map[key];
+        let new_key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        
+        match v {
+            Value::Object(_) => flatten_ordered_into(v, &new_key, result),
+            Value::Array(arr) => {
+                result.push((new_key.clone(), v.clone()));
+                for (i, item) in arr.iter().enumerate() {
+                    let array_key = format!("{}[{}]", new_key, i);
+                    match item {
+                        Value::Object(_) => flatten_ordered_into(item, &array_key, result),
+                        _ => result.push((array_key, item.clone())),
+                    }
+                }
+            },
+            _ => result.push((new_key, v.clone())),
+        }
+    }
+}
+
 /// Create a structured JSON error message
 pub fn json_error(message: &str, code: Option<&str>) -> Value {
     let mut obj = serde_json::Map::new();
@@ -296,6 +523,278 @@ pub fn is_empty_value(value: &Value) -> bool {
     }
 }
 
+/// Check whether raw JSON text nests arrays/objects deeper than `max_depth`
+///
+/// Scans the text without parsing it, skipping brackets inside strings,
+/// so it is safe to run on untrusted input before deserializing.
+pub fn json_depth_exceeds(input: &str, max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    
+    for byte in input.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {},
+            }
+            continue;
+        }
+        
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            },
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+    }
+    
+    false
+}
+
+/// Serialize entries to JSON strings in parallel, preserving order
+///
+/// Work is spread over the global rayon thread pool. Serialization stops
+/// at the first failing entry and its error is returned; no partial
+/// results are produced.
+#[cfg(feature = "rayon")]
+pub fn serialize_batch_parallel(entries: &[LogEntry]) -> Result<Vec<String>> {
+    use rayon::prelude::*;
+    
+    entries.par_iter().map(LogEntry::to_json).collect()
+}
+
+/// Serialize entries to NDJSON and gzip the result
+///
+/// `level` is the gzip compression level, from 0 (none) to 9 (best);
+/// higher values are clamped to 9.
+#[cfg(feature = "gzip")]
+pub fn compress_batch(entries: &[LogEntry], level: u32) -> Result<Vec<u8>> {
+    use std::io::Write;
+    use flate2::{Compression, write::GzEncoder};
+    
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level.min(9)));
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry).map_err(Error::SerializationError)?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Decompress a batch produced by `compress_batch` back into entries
+///
+/// Fails with `Error::IoError` on corrupt gzip data and with
+/// `Error::ParseError` on the first malformed line.
+#[cfg(feature = "gzip")]
+pub fn decompress_batch(bytes: &[u8]) -> Result<Vec<LogEntry>> {
+    use std::io::BufReader;
+    use flate2::read::GzDecoder;
+    
+    crate::ndjson::NdjsonReader::new(BufReader::new(GzDecoder::new(bytes))).collect()
+}
+
+/// Stream NDJSON from `reader` through `processor` into `writer`
+///
+/// Each line is parsed, run through the processor's filters, redactor and
+/// formatter and written as one output line. Malformed lines and formatting
+/// failures are counted, not fatal; I/O errors abort processing, as does a
+/// formatter producing multi-line records (e.g. `PrettyFormatter`), which
+/// would break the one-record-per-line contract.
+pub fn process_ndjson<R, W, F>(
+    reader: R,
+    mut writer: W,
+    processor: &crate::pipeline::EntryProcessor<F>,
+) -> Result<crate::pipeline::ProcessStats>
+where
+    R: std::io::BufRead,
+    W: std::io::Write,
+    F: crate::formatter::Formatter,
+{
+    let mut stats = crate::pipeline::ProcessStats::default();
+    
+    for result in crate::ndjson::NdjsonReader::new(reader) {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(Error::ParseError { .. }) => {
+                stats.read += 1;
+                stats.errored += 1;
+                continue;
+            },
+            Err(e) => return Err(e),
+        };
+        stats.read += 1;
+        
+        match processor.apply(entry) {
+            None => stats.filtered += 1,
+            Some(Ok(line)) => {
+                if line.contains(['\n', '\r']) {
+                    return Err(Error::FormatterError(
+                        "NDJSON output needs a formatter producing single-line records".to_string()
+                    ));
+                }
+                writeln!(writer, "{}", line)?;
+                stats.written += 1;
+            },
+            Some(Err(_)) => stats.errored += 1,
+        }
+    }
+    
+    writer.flush()?;
+    Ok(stats)
+}
+
+/// Split entries into chunks of at most `max_count` entries or `max_bytes`
+/// of serialized JSON, whichever limit is reached first
+///
+/// Sizes come from `LogEntry::estimated_json_size`. An entry larger than
+/// `max_bytes` on its own becomes a single-entry chunk. Order is preserved.
+pub fn chunk_entries<I>(entries: I, max_count: usize, max_bytes: usize) -> Vec<Vec<LogEntry>>
+where
+    I: IntoIterator<Item = LogEntry>,
+{
+    let max_count = max_count.max(1);
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes = 0;
+    
+    for entry in entries {
+        let size = entry.estimated_json_size();
+        let over_bytes = current_bytes + size > max_bytes;
+        
+        if !current.is_empty() && (current.len() >= max_count || over_bytes) {
+            chunks.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        
+        current_bytes += size;
+        current.push(entry);
+    }
+    
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Anonymize an IP address for privacy compliance
+///
+/// Zeroes the last octet of IPv4 addresses and the last 80 bits of IPv6
+/// addresses. Strings that aren't valid IP addresses are returned unchanged.
+pub fn anonymize_ip(ip: &str) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(addr)) => {
+            let [a, b, c, _] = addr.octets();
+            Ipv4Addr::new(a, b, c, 0).to_string()
+        },
+        Ok(IpAddr::V6(addr)) => {
+            let masked = u128::from(addr) & !((1u128 << 80) - 1);
+            Ipv6Addr::from(masked).to_string()
+        },
+        Err(_) => ip.to_string(),
+    }
+}
+
+/// Serialize a JSON value in canonical form (RFC 8785 style)
+///
+/// Object keys are sorted by their UTF-16 code units, no insignificant
+/// whitespace is emitted and numbers use a single normalized representation,
+/// so logically-equal values always produce byte-identical output.
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&canonical_number(n)),
+        Value::String(s) => write_canonical_string(s, out),
+        Value::Array(arr) => {
+            out.push('[');
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        },
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_string(key, out);
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        },
+    }
+}
+
+fn write_canonical_string(s: &str, out: &mut String) {
+    // serde_json only escapes what JSON requires, matching RFC 8785
+    out.push_str(&serde_json::to_string(s).unwrap_or_default());
+}
+
+/// Format a number the way ECMAScript's `Number.prototype.toString` does
+fn canonical_number(n: &serde_json::Number) -> String {
+    if n.is_i64() || n.is_u64() {
+        return n.to_string();
+    }
+    
+    let f = match n.as_f64() {
+        Some(f) if f.is_finite() => f,
+        _ => return "null".to_string(),
+    };
+    if f == 0.0 {
+        return "0".to_string();
+    }
+    
+    // Shortest round-trip digits and exponent, e.g. "1.2345e-7"
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap_or(0) + 1;
+    
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{}e{}{}", first, sign, (n - 1).abs())
+        } else {
+            format!("{}.{}e{}{}", first, rest, sign, (n - 1).abs())
+        }
+    };
+    
+    if f < 0.0 {
+        format!("-{}", body)
+    } else {
+        body
+    }
+}
+
 /// Return the stacktrace of the current execution point
 #[cfg(debug_assertions)]
 pub fn get_stacktrace() -> String {
@@ -308,6 +807,90 @@ pub fn get_stacktrace() -> String {
     "Stack traces only available in debug mode".to_string()
 }
 
+/// A single frame of a captured stack trace
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StackFrame {
+    /// Function (symbol) name, if resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// Source file, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Line number, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Capture the current stack as structured frames
+///
+/// Like `get_stacktrace`, this only works in debug builds; release builds
+/// return an empty list.
+#[cfg(debug_assertions)]
+pub fn capture_frames() -> Vec<StackFrame> {
+    use std::backtrace::Backtrace;
+    parse_backtrace(&Backtrace::force_capture().to_string())
+}
+
+#[cfg(not(debug_assertions))]
+pub fn capture_frames() -> Vec<StackFrame> {
+    Vec::new()
+}
+
+/// Parse the display form of a `std::backtrace::Backtrace`
+///
+/// Frames look like `  3: some::function` optionally followed by a line
+/// `at ./src/file.rs:12:5`.
+fn parse_backtrace(text: &str) -> Vec<StackFrame> {
+    let mut frames: Vec<StackFrame> = Vec::new();
+    
+    for line in text.lines().map(str::trim) {
+        if let Some(location) = line.strip_prefix("at ") {
+            let Some(frame) = frames.last_mut() else {
+                continue;
+            };
+            // Location is `file:line:column`; the file may itself contain ':'
+            let mut parts = location.rsplitn(3, ':');
+            let _column = parts.next();
+            let line_no = parts.next().and_then(|l| l.parse().ok());
+            match (parts.next(), line_no) {
+                (Some(file), Some(line_no)) => {
+                    frame.file = Some(file.to_string());
+                    frame.line = Some(line_no);
+                },
+                _ => frame.file = Some(location.to_string()),
+            }
+        } else if let Some((index, function)) = line.split_once(": ") {
+            if index.chars().all(|c| c.is_ascii_digit()) && !index.is_empty() {
+                frames.push(StackFrame {
+                    function: Some(function.to_string()),
+                    file: None,
+                    line: None,
+                });
+            }
+        }
+    }
+    
+    frames
+}
+
+/// Levenshtein edit distance between two strings, counted in chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    
+    prev[b.len()]
+}
+
 /// Calculate a simple hash of a string, useful for identifying logs
 pub fn simple_hash(s: &str) -> u64 {
     let mut hash: u64 = 5381;
@@ -317,6 +900,19 @@ pub fn simple_hash(s: &str) -> u64 {
     hash
 }
 
+/// Calculate the CRC-32 (IEEE) checksum of a byte slice
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
 /// Estimate the JSON size of a String
 pub fn estimate_json_string_size(s: &str) -> usize {
     // Account for quotes and possible escaping
@@ -328,6 +924,18 @@ pub fn hashmap_to_json<T: serde::Serialize>(map: &HashMap<String, T>) -> Result<
     serde_json::to_value(map).map_err(Error::SerializationError)
 }
 
+/// Convert entries into a JSON array value
+///
+/// Useful for embedding logs in a larger JSON document without
+/// serializing to a string and parsing it again.
+pub fn entries_to_value(entries: &[LogEntry]) -> Result<Value> {
+    entries
+        .iter()
+        .map(LogEntry::to_value)
+        .collect::<Result<Vec<_>>>()
+        .map(Value::Array)
+}
+
 /// Get a random log ID
 ///
 /// Format: YYYY-MM-DD-RANDOM
@@ -363,6 +971,190 @@ where
 mod tests {
     use super::*;
     
+    #[cfg(feature = "timezones")]
+    #[test]
+    fn test_format_timestamp_tz() {
+        let winter: DateTime<Utc> = "2024-01-15T12:30:00Z".parse().unwrap();
+        let summer: DateTime<Utc> = "2024-07-15T12:30:00Z".parse().unwrap();
+        
+        let ny = chrono_tz::America::New_York;
+        assert_eq!(format_timestamp_tz(&winter, ny), "2024-01-15T07:30:00.000-05:00");
+        assert_eq!(format_timestamp_tz(&summer, ny), "2024-07-15T08:30:00.000-04:00");
+        assert_eq!(format_timestamp_tz(&winter, chrono_tz::UTC), "2024-01-15T12:30:00.000+00:00");
+    }
+    
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_compress_batch_round_trip() {
+        let entries: Vec<LogEntry> = (0..100)
+            .map(|i| {
+                let mut entry = LogEntry::new(format!("upload chunk {}", i), LogLevel::Info);
+                entry.add_context("chunk", i).unwrap();
+                entry
+            })
+            .collect();
+        
+        let compressed = compress_batch(&entries, 6).unwrap();
+        let uncompressed_size: usize = entries.iter().map(|e| e.to_json().unwrap().len() + 1).sum();
+        assert!(compressed.len() < uncompressed_size);
+        
+        let restored = decompress_batch(&compressed).unwrap();
+        assert_eq!(restored.len(), entries.len());
+        for (restored, original) in restored.iter().zip(&entries) {
+            assert!(restored.semantically_eq(original));
+            assert_eq!(restored.metadata.id, original.metadata.id);
+        }
+        
+        assert!(matches!(decompress_batch(b"not gzip"), Err(Error::IoError(_))));
+    }
+    
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_serialize_batch_parallel() {
+        let entries: Vec<LogEntry> = (0..1000)
+            .map(|i| LogEntry::new(format!("entry {}", i), LogLevel::Info))
+            .collect();
+        
+        let parallel = serialize_batch_parallel(&entries).unwrap();
+        let sequential: Vec<String> = entries.iter().map(|e| e.to_json().unwrap()).collect();
+        assert_eq!(parallel, sequential);
+    }
+    
+    #[test]
+    fn test_flatten_json_depth() {
+        let value = serde_json::json!({"a": {"b": {"c": 1}}, "top": true});
+        
+        let depth1 = flatten_json_depth(&value, "", 1);
+        assert_eq!(depth1.len(), 2);
+        assert_eq!(depth1["a"], serde_json::json!({"b": {"c": 1}}));
+        assert_eq!(depth1["top"], true);
+        
+        let depth2 = flatten_json_depth(&value, "", 2);
+        assert_eq!(depth2.len(), 2);
+        assert_eq!(depth2["a.b"], serde_json::json!({"c": 1}));
+        
+        let full = flatten_json(&value, "");
+        assert_eq!(full["a.b.c"], 1);
+        assert_eq!(flatten_json_depth(&value, "", 3), full);
+    }
+    
+    #[test]
+    fn test_flatten_json_ordered() {
+        let value = serde_json::json!({
+            "zeta": 1,
+            "alpha": {"b": true, "a": null},
+            "list": [3, {"x": "y"}],
+        });
+        
+        let flat = flatten_json_ordered(&value, "");
+        let keys: Vec<&str> = flat.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["alpha.a", "alpha.b", "list", "list[0]", "list[1].x", "zeta"]);
+        
+        for _ in 0..10 {
+            assert_eq!(flatten_json_ordered(&value, ""), flat);
+        }
+        
+        let unordered = flatten_json(&value, "");
+        assert_eq!(unordered.len(), flat.len());
+        assert!(flat.iter().all(|(k, v)| unordered.get(k) == Some(v)));
+    }
+    
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "warn"), 4);
+        assert_eq!(edit_distance("waring", "warn"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+    
+    #[test]
+    fn test_merge_json_values_with_array_modes() {
+        let base = serde_json::json!({"tags": ["db", "slow"], "meta": {"ids": [1]}});
+        let update = serde_json::json!({"tags": ["slow", "retry"], "meta": {"ids": [2]}});
+        
+        let replaced = merge_json_values_with(&base, &update, ArrayMergeMode::Replace);
+        assert_eq!(replaced["tags"], serde_json::json!(["slow", "retry"]));
+        assert_eq!(replaced, merge_json_values(&base, &update));
+        
+        let concat = merge_json_values_with(&base, &update, ArrayMergeMode::Concat);
+        assert_eq!(concat["tags"], serde_json::json!(["db", "slow", "slow", "retry"]));
+        assert_eq!(concat["meta"]["ids"], serde_json::json!([1, 2]));
+        
+        let deduped = merge_json_values_with(&base, &update, ArrayMergeMode::ConcatDedup);
+        assert_eq!(deduped["tags"], serde_json::json!(["db", "slow", "retry"]));
+    }
+    
+    #[test]
+    fn test_entries_to_value() {
+        let entries = vec![
+            LogEntry::new("first", LogLevel::Info),
+            LogEntry::new("second", LogLevel::Error),
+        ];
+        
+        let value = entries_to_value(&entries).unwrap();
+        let array = value.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert!(array.iter().all(Value::is_object));
+        assert_eq!(array[1]["message"], "second");
+        assert_eq!(entries_to_value(&[]).unwrap(), serde_json::json!([]));
+    }
+    
+    #[test]
+    fn test_format_iso_duration() {
+        assert_eq!(format_iso_duration(0), "PT0S");
+        assert_eq!(format_iso_duration(250), "PT0.25S");
+        assert_eq!(format_iso_duration(1_005), "PT1.005S");
+        assert_eq!(format_iso_duration(65_000), "PT1M5S");
+        assert_eq!(format_iso_duration(120_000), "PT2M");
+        assert_eq!(format_iso_duration(3_723_400), "PT1H2M3.4S");
+        assert_eq!(format_iso_duration(2 * 3_600_000), "PT2H");
+        assert_eq!(format_iso_duration(-1_500), "-PT1.5S");
+    }
+    
+    #[test]
+    fn test_process_ndjson() {
+        use crate::filter::LevelFilter;
+        use crate::formatter::{PrettyFormatter, SimpleFormatter};
+        use crate::pipeline::{EntryProcessor, ProcessStats};
+        use crate::redact::Redactor;
+        
+        let mut login = LogEntry::new("login", LogLevel::Warn);
+        login.add_context("password", "hunter2").unwrap();
+        let input = format!(
+            "{}\n{{broken\n\n{}\n",
+            login.to_json().unwrap(),
+            LogEntry::new("noise", LogLevel::Debug).to_json().unwrap(),
+        );
+        
+        let processor = EntryProcessor::new(SimpleFormatter::new())
+            .filter(LevelFilter::new(LogLevel::Info))
+            .redactor(Redactor::new(["password"]));
+        let mut output = Vec::new();
+        let stats = process_ndjson(input.as_bytes(), &mut output, &processor).unwrap();
+        
+        assert_eq!(stats, ProcessStats { read: 3, written: 1, filtered: 1, errored: 1 });
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("[REDACTED]"));
+        assert!(!output.contains("hunter2"));
+        
+        let pretty = EntryProcessor::new(PrettyFormatter::new());
+        let result = process_ndjson(input.as_bytes(), Vec::new(), &pretty);
+        assert!(matches!(result, Err(Error::FormatterError(_))));
+    }
+    
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+    
+    #[test]
+    fn test_json_depth_exceeds() {
+        assert!(!json_depth_exceeds(r#"{"a":[1,{"b":2}]}"#, 3));
+        assert!(json_depth_exceeds(r#"{"a":[1,{"b":2}]}"#, 2));
+        assert!(!json_depth_exceeds(r#"{"a":"[[[[\"{{"}"#, 1));
+    }
+    
     #[test]
     fn test_sanitize_field_name() {
         assert_eq!(sanitize_field_name("user.name"), "user_name");
@@ -386,6 +1178,14 @@ mod tests {
         assert_eq!(truncate_string("abcdefghijklmnop", 10), "abcdefg...");
     }
     
+    #[test]
+    fn test_truncate_string_with() {
+        assert_eq!(truncate_string_with("hello world", 6, "\u{2026}"), "hello\u{2026}");
+        assert_eq!(truncate_string_with("hello world", 20, "\u{2026}"), "hello world");
+        assert_eq!(truncate_string_with("a long log message", 15, "[truncated]"), "a lo[truncated]");
+        assert_eq!(truncate_string_with("h\u{e9}llo w\u{f6}rld", 8, "..."), "h\u{e9}llo...");
+    }
+    
     #[test]
     fn test_string_to_log_level() {
         assert_eq!(string_to_log_level("trace"), LogLevel::Trace);
@@ -458,6 +1258,31 @@ mod tests {
         assert_eq!(merge_json_values(&base, &update), expected);
     }
     
+    #[test]
+    fn test_json_diff() {
+        let base = serde_json::json!({"a": 1, "b": {"c": 2, "d": 3}, "e": [1]});
+        let target = serde_json::json!({"a": 1, "b": {"c": 5, "d": 3}, "e": [1, 2], "f": true});
+        
+        let diff = json_diff(&base, &target).unwrap();
+        assert_eq!(diff, serde_json::json!({"b": {"c": 5}, "e": [1, 2], "f": true}));
+        assert_eq!(apply_json_diff(&base, &diff), target);
+        assert_eq!(json_diff(&base, &base), None);
+    }
+    
+    #[test]
+    fn test_json_diff_removal_and_null() {
+        let base = serde_json::json!({"a": 1, "b": {"c": 2, "d": 3}, "e": "x"});
+        let target = serde_json::json!({"a": null, "b": {"c": 2}});
+        
+        let diff = json_diff(&base, &target).unwrap();
+        assert_eq!(diff, serde_json::json!({
+            "a": null,
+            "b": {"$removed": ["d"]},
+            "$removed": ["e"],
+        }));
+        assert_eq!(apply_json_diff(&base, &diff), target);
+    }
+    
     #[test]
     fn test_flatten_json() {
         let json = serde_json::json!({
@@ -492,6 +1317,90 @@ mod tests {
         assert!(!is_empty_value(&Value::Array(vec![Value::Null])));
     }
     
+    fn sized_entries(count: usize, payload: usize) -> Vec<LogEntry> {
+        (0..count)
+            .map(|i| {
+                let mut entry = LogEntry::new(format!("entry {}", i), LogLevel::Info);
+                entry.add_context("payload", "x".repeat(payload)).unwrap();
+                entry
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn test_chunk_entries_by_count() {
+        let chunks = chunk_entries(sized_entries(7, 10), 3, usize::MAX);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![3, 3, 1]);
+        assert_eq!(chunks[2][0].message, "entry 6");
+    }
+    
+    #[test]
+    fn test_chunk_entries_by_bytes() {
+        let entries = sized_entries(6, 100);
+        let size = entries[0].estimated_json_size();
+        
+        let chunks = chunk_entries(entries, 100, size * 2 + size / 2);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2, 2, 2]);
+    }
+    
+    #[test]
+    fn test_chunk_entries_oversized_singleton() {
+        let mut entries = sized_entries(2, 10);
+        entries.insert(1, sized_entries(1, 5000).remove(0));
+        
+        let chunks = chunk_entries(entries, 100, 1000);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![1, 1, 1]);
+        assert!(chunks[1][0].estimated_json_size() > 1000);
+    }
+    
+    #[test]
+    fn test_anonymize_ip() {
+        assert_eq!(anonymize_ip("192.168.1.42"), "192.168.1.0");
+        assert_eq!(anonymize_ip("2001:db8:85a3:8d3:1319:8a2e:370:7348"), "2001:db8:85a3::");
+        assert_eq!(anonymize_ip("not-an-ip"), "not-an-ip");
+        assert_eq!(anonymize_ip("192.168.1"), "192.168.1");
+    }
+    
+    #[test]
+    fn test_canonical_json() {
+        let value = serde_json::json!({
+            "b": [1.0, 2.5, 1e21, 0.000001, 1e-7, -0.0],
+            "a": {"z": null, "y": "line\nbreak"}
+        });
+        
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":{"y":"line\nbreak","z":null},"b":[1,2.5,1e+21,0.000001,1e-7,0]}"#
+        );
+    }
+    
+    #[test]
+    fn test_parse_backtrace() {
+        let text = "   0: app::handler\n             at ./src/handler.rs:42:9\n   1: std::rt::lang_start\n   2: C:\\app\\main\n             at C:\\app\\src\\main.rs:7:1\n";
+        let frames = parse_backtrace(text);
+        
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].function.as_deref(), Some("app::handler"));
+        assert_eq!(frames[0].file.as_deref(), Some("./src/handler.rs"));
+        assert_eq!(frames[0].line, Some(42));
+        assert_eq!(frames[1].file, None);
+        assert_eq!(frames[2].file.as_deref(), Some("C:\\app\\src\\main.rs"));
+        assert_eq!(frames[2].line, Some(7));
+    }
+    
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_capture_frames() {
+        let frames = capture_frames();
+        assert!(!frames.is_empty());
+        assert!(frames.iter().any(|frame| {
+            frame.function.as_deref().is_some_and(|f| f.contains("test_capture_frames"))
+        }));
+    }
+    
     #[test]
     fn test_simple_hash() {
         let hash1 = simple_hash("hello");
@@ -0,0 +1,86 @@
+//! Custom JSON representations for context value types
+//!
+//! Some types (addresses, durations, newtypes) should appear in logs in a
+//! canonical form rather than serde's default representation. A
+//! `ValueFormatterRegistry` maps a type to the function producing that form.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{Result, Error};
+
+type ValueFormatFn = Box<dyn Fn(&dyn Any) -> Option<Value> + Send + Sync>;
+
+/// Registry of per-type JSON formatters for context values
+#[derive(Default)]
+pub struct ValueFormatterRegistry {
+    formatters: HashMap<TypeId, ValueFormatFn>,
+}
+
+impl ValueFormatterRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Register the formatter used for values of type `T`
+    ///
+    /// Replaces any formatter previously registered for `T`.
+    pub fn register<T: 'static>(&mut self, f: fn(&T) -> Value) -> &mut Self {
+        self.formatters.insert(
+            TypeId::of::<T>(),
+            Box::new(move |value: &dyn Any| value.downcast_ref::<T>().map(f)),
+        );
+        self
+    }
+    
+    /// Check if a formatter is registered for `T`
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.formatters.contains_key(&TypeId::of::<T>())
+    }
+    
+    /// Format a value, falling back to serde when no formatter is registered
+    pub fn format<T: Serialize + 'static>(&self, value: &T) -> Result<Value> {
+        if let Some(formatter) = self.formatters.get(&TypeId::of::<T>()) {
+            if let Some(formatted) = formatter(value) {
+                return Ok(formatted);
+            }
+        }
+        serde_json::to_value(value).map_err(Error::SerializationError)
+    }
+}
+
+impl std::fmt::Debug for ValueFormatterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValueFormatterRegistry")
+            .field("formatters", &self.formatters.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{LogEntry, LogLevel};
+    
+    #[derive(Serialize)]
+    struct Celsius(f64);
+    
+    #[test]
+    fn test_registered_formatter_is_used() {
+        let mut registry = ValueFormatterRegistry::new();
+        registry.register::<Celsius>(|c| serde_json::json!({ "value": c.0, "unit": "C" }));
+        
+        let mut entry = LogEntry::new("Temperature reading", LogLevel::Info);
+        entry.add_context_custom("temperature", Celsius(21.5), &registry).unwrap();
+        entry.add_context_custom("sensor", "north-wing", &registry).unwrap();
+        
+        assert_eq!(
+            entry.context["temperature"],
+            serde_json::json!({ "value": 21.5, "unit": "C" })
+        );
+        assert_eq!(entry.context["sensor"], serde_json::json!("north-wing"));
+    }
+}